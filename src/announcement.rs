@@ -0,0 +1,110 @@
+//! Announcements module (`/api/v3/announcements`).
+
+use chrono::{DateTime, Local};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ServiceDesk,
+    builders::serialize_optional_sdp_time,
+    client::{TimeEntry, deserialize_optional_name_object, serialize_optional_name_object},
+    error::Error,
+};
+
+/// An announcement as returned by SDP.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Announcement {
+    pub id: String,
+    pub title: String,
+    pub description: Option<String>,
+    #[serde(
+        rename = "type",
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_name_object",
+        deserialize_with = "deserialize_optional_name_object"
+    )]
+    pub announcement_type: Option<String>,
+    /// Audience of the announcement, e.g. "all" or "technicians"
+    pub show_to: Option<String>,
+    pub from_date: Option<TimeEntry>,
+    pub to_date: Option<TimeEntry>,
+}
+
+/// Data for creating an announcement. Unset optional fields are omitted.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AnnouncementData {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(
+        rename = "type",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_name_object"
+    )]
+    pub announcement_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show_to: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_sdp_time"
+    )]
+    pub from_date: Option<DateTime<Local>>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_sdp_time"
+    )]
+    pub to_date: Option<DateTime<Local>>,
+}
+
+#[derive(Serialize, Debug)]
+struct CreateAnnouncementRequest<'a> {
+    announcement: &'a AnnouncementData,
+}
+
+#[derive(Deserialize, Debug)]
+struct AnnouncementResponse {
+    announcement: Announcement,
+}
+
+#[derive(Deserialize, Debug)]
+struct AnnouncementsListResponse {
+    #[serde(default)]
+    announcements: Vec<Announcement>,
+}
+
+impl ServiceDesk {
+    /// List announcements.
+    pub async fn list_announcements(&self) -> Result<Vec<Announcement>, Error> {
+        tracing::info!("listing announcements");
+        let resp: AnnouncementsListResponse = self
+            .request_with_path(Method::GET, "/api/v3/announcements")
+            .await?;
+        Ok(resp.announcements)
+    }
+
+    /// Get a single announcement.
+    pub async fn get_announcement(&self, id: &str) -> Result<Announcement, Error> {
+        tracing::info!(announcement_id = %id, "fetching announcement");
+        let resp: AnnouncementResponse = self
+            .request_with_path(Method::GET, &format!("/api/v3/announcements/{}", id))
+            .await?;
+        Ok(resp.announcement)
+    }
+
+    /// Create a new announcement.
+    pub async fn create_announcement(
+        &self,
+        data: &AnnouncementData,
+    ) -> Result<Announcement, Error> {
+        tracing::info!(title = %data.title, "creating announcement");
+        let resp: AnnouncementResponse = self
+            .request_input_data(
+                Method::POST,
+                "/api/v3/announcements",
+                &CreateAnnouncementRequest { announcement: data },
+            )
+            .await?;
+        Ok(resp.announcement)
+    }
+}
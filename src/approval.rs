@@ -0,0 +1,228 @@
+//! Request approvals (`/api/v3/requests/{id}/approval_levels`).
+
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ServiceDesk, TicketID,
+    client::{UserInfo, deserialize_optional_name_object},
+    error::Error,
+};
+
+/// Overall approval state of a request, or the state of a single approval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ApprovalStatus {
+    /// No approval has been sent out yet.
+    NotSent,
+    /// At least one approval is still awaiting a decision.
+    Pending,
+    /// Every approval was approved.
+    Approved,
+    /// At least one approval was rejected.
+    Rejected,
+}
+
+impl ApprovalStatus {
+    /// Map an SDP approval status name, e.g. "Pending Approval" or "Yet to send".
+    /// Unknown names are treated as pending.
+    pub fn from_name(name: &str) -> Self {
+        let name = name.to_ascii_lowercase();
+        if name.contains("reject") || name.contains("denied") {
+            ApprovalStatus::Rejected
+        } else if name.contains("approved") {
+            ApprovalStatus::Approved
+        } else if name.contains("yet to send") || name.contains("not sent") {
+            ApprovalStatus::NotSent
+        } else {
+            ApprovalStatus::Pending
+        }
+    }
+
+    /// Reduce individual approvals to a single answer.
+    ///
+    /// Any rejection wins, then any approval still in flight. Approvals that
+    /// were never sent only count as `NotSent` when nothing else was sent
+    /// either; otherwise the request is still `Pending` on a later level.
+    pub fn summarize<'a>(approvals: impl IntoIterator<Item = &'a Approval>) -> Self {
+        let mut any_sent = false;
+        let mut any_unsent = false;
+        let mut any_pending = false;
+        for approval in approvals {
+            match approval.status() {
+                ApprovalStatus::Rejected => return ApprovalStatus::Rejected,
+                ApprovalStatus::Pending => {
+                    any_sent = true;
+                    any_pending = true;
+                }
+                ApprovalStatus::Approved => any_sent = true,
+                ApprovalStatus::NotSent => any_unsent = true,
+            }
+        }
+        match (any_sent, any_pending || any_unsent) {
+            (false, _) => ApprovalStatus::NotSent,
+            (true, true) => ApprovalStatus::Pending,
+            (true, false) => ApprovalStatus::Approved,
+        }
+    }
+}
+
+/// A single approval on a request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Approval {
+    pub id: String,
+    /// SDP status name, e.g. "Pending Approval" or "Approved".
+    #[serde(default, deserialize_with = "deserialize_optional_name_object")]
+    pub status: Option<String>,
+    pub approver: Option<UserInfo>,
+    pub comments: Option<String>,
+}
+
+impl Approval {
+    /// Status of this approval; a missing status means it has not been sent.
+    pub fn status(&self) -> ApprovalStatus {
+        self.status
+            .as_deref()
+            .map_or(ApprovalStatus::NotSent, ApprovalStatus::from_name)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct ApprovalLevel {
+    id: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct ApprovalLevelsResponse {
+    #[serde(default)]
+    approval_levels: Vec<ApprovalLevel>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ApprovalsListResponse {
+    #[serde(default)]
+    approvals: Vec<Approval>,
+}
+
+impl ServiceDesk {
+    /// List the approvals of a request across all of its approval levels.
+    pub async fn list_approvals(
+        &self,
+        ticket_id: impl Into<TicketID>,
+    ) -> Result<Vec<Approval>, Error> {
+        let ticket_id = ticket_id.into();
+        tracing::info!(ticket_id = %ticket_id, "listing approvals");
+        let levels: ApprovalLevelsResponse = self
+            .request_with_path(
+                Method::GET,
+                &format!("/api/v3/requests/{}/approval_levels", ticket_id),
+            )
+            .await?;
+
+        let mut approvals = Vec::new();
+        for level in levels.approval_levels {
+            let resp: ApprovalsListResponse = self
+                .request_with_path(
+                    Method::GET,
+                    &format!(
+                        "/api/v3/requests/{}/approval_levels/{}/approvals",
+                        ticket_id, level.id
+                    ),
+                )
+                .await?;
+            approvals.extend(resp.approvals);
+        }
+        Ok(approvals)
+    }
+
+    /// Summarize the approvals of a request into a single [`ApprovalStatus`].
+    pub async fn approval_status(
+        &self,
+        ticket_id: impl Into<TicketID>,
+    ) -> Result<ApprovalStatus, Error> {
+        let approvals = self.list_approvals(ticket_id).await?;
+        Ok(ApprovalStatus::summarize(&approvals))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn approvals(statuses: &[Option<&str>]) -> Vec<Approval> {
+        statuses
+            .iter()
+            .enumerate()
+            .map(|(i, status)| Approval {
+                id: i.to_string(),
+                status: status.map(str::to_string),
+                approver: None,
+                comments: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn from_name_maps_sdp_status_names() {
+        assert_eq!(
+            ApprovalStatus::from_name("Pending Approval"),
+            ApprovalStatus::Pending
+        );
+        assert_eq!(
+            ApprovalStatus::from_name("Approved"),
+            ApprovalStatus::Approved
+        );
+        assert_eq!(
+            ApprovalStatus::from_name("Rejected"),
+            ApprovalStatus::Rejected
+        );
+        assert_eq!(
+            ApprovalStatus::from_name("Yet to send"),
+            ApprovalStatus::NotSent
+        );
+    }
+
+    #[test]
+    fn summarize_mixed_states() {
+        let cases = [
+            (vec![], ApprovalStatus::NotSent),
+            (vec![Some("Yet to send"), None], ApprovalStatus::NotSent),
+            (
+                vec![Some("Approved"), Some("Pending Approval")],
+                ApprovalStatus::Pending,
+            ),
+            (
+                vec![Some("Approved"), Some("Yet to send")],
+                ApprovalStatus::Pending,
+            ),
+            (
+                vec![Some("Approved"), Some("Rejected"), Some("Pending Approval")],
+                ApprovalStatus::Rejected,
+            ),
+            (
+                vec![Some("Approved"), Some("Approved")],
+                ApprovalStatus::Approved,
+            ),
+        ];
+        for (statuses, expected) in cases {
+            assert_eq!(
+                ApprovalStatus::summarize(&approvals(&statuses)),
+                expected,
+                "{statuses:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn approval_deserializes_status_object() {
+        let approval: Approval = serde_json::from_value(json!({
+            "id": "3",
+            "status": { "id": "1", "name": "Approved" },
+            "approver": { "id": "7", "name": "Manager" },
+        }))
+        .unwrap();
+
+        assert_eq!(approval.status(), ApprovalStatus::Approved);
+        assert_eq!(approval.approver.unwrap().name, "Manager");
+    }
+}
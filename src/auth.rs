@@ -1,3 +1,10 @@
+use reqwest::{
+    RequestBuilder,
+    header::{HeaderName, HeaderValue},
+};
+
+use crate::Error;
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Credentials {
     /// Unimplemented
@@ -5,3 +12,60 @@ pub enum Credentials {
     /// Bearer token authentication
     Token { token: String },
 }
+
+/// Where the auth token is attached on outgoing requests.
+///
+/// Cloud and recent on-prem builds read the `authtoken` header, older on-prem
+/// builds expect a `TECHNICIAN_KEY` query parameter instead.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum TokenLocation {
+    /// `authtoken: <token>` header (default)
+    #[default]
+    AuthTokenHeader,
+    /// `?TECHNICIAN_KEY=<token>` query parameter
+    TechnicianKeyQuery,
+    /// Header with a custom name
+    CustomHeader(String),
+}
+
+/// Validated form of the credentials, ready to be attached to each request.
+#[derive(Clone, Debug)]
+pub(crate) enum Auth {
+    None,
+    Header(HeaderName, HeaderValue),
+    Query(String),
+}
+
+impl Auth {
+    pub(crate) fn new(credentials: &Credentials, location: &TokenLocation) -> Result<Self, Error> {
+        let Credentials::Token { token } = credentials else {
+            return Ok(Auth::None);
+        };
+
+        let header_value = || {
+            HeaderValue::from_str(token)
+                .map_err(|e| Error::Other(format!("invalid auth token header value: {e}")))
+        };
+
+        match location {
+            TokenLocation::AuthTokenHeader => Ok(Auth::Header(
+                HeaderName::from_static("authtoken"),
+                header_value()?,
+            )),
+            TokenLocation::CustomHeader(name) => {
+                let name = HeaderName::from_bytes(name.as_bytes())
+                    .map_err(|e| Error::Other(format!("invalid auth header name: {e}")))?;
+                Ok(Auth::Header(name, header_value()?))
+            }
+            TokenLocation::TechnicianKeyQuery => Ok(Auth::Query(token.clone())),
+        }
+    }
+
+    pub(crate) fn apply(&self, builder: RequestBuilder) -> RequestBuilder {
+        match self {
+            Auth::None => builder,
+            Auth::Header(name, value) => builder.header(name, value),
+            Auth::Query(token) => builder.query(&[("TECHNICIAN_KEY", token)]),
+        }
+    }
+}
@@ -31,18 +31,25 @@
 //! # }
 //! ```
 
+use std::collections::HashMap;
 use std::path::Path;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
 
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, Utc};
+use futures_util::{Stream, StreamExt, stream};
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::{
-    Priority, ServiceDesk, TicketID, UserInfo,
+    ApprovalStatus, Priority, ServiceDesk, Tag, TicketID, UserInfo,
     client::{
-        Condition, CreateTicketData, Criteria, DetailedTicket, EditTicketData, ListInfo, LogicalOp,
-        Note, NoteData, SearchRequest, TicketData, TicketSearchResponse,
+        Account, Association, BATCH_CONCURRENCY, Condition, ConversationSummary, CreateTicketData,
+        Criteria, DetailedTicket, EditTicketData, ListInfo, LogicalOp, MergeResult, Note, NoteData,
+        OperationResult, ReplyData, SearchPage, Sla, Template, ThreadItem, TicketData,
+        TicketSearchResponse,
     },
     error::Error,
 };
@@ -60,9 +67,77 @@ impl<'a> TicketsClient<'a> {
             root_criteria: None,
             children: vec![],
             row_count: 100,
+            include_trashed: false,
         }
     }
 
+    /// Start a permanent deletion of trashed tickets.
+    ///
+    /// Nothing is deleted unless [`confirm`](EmptyTrashBuilder::confirm) is
+    /// given the number of ids passed here.
+    pub fn empty_trash(
+        self,
+        ticket_ids: impl IntoIterator<Item = impl Into<TicketID>>,
+    ) -> EmptyTrashBuilder<'a> {
+        EmptyTrashBuilder {
+            client: self.client,
+            ids: collect_ids(ticket_ids),
+            confirmed: None,
+        }
+    }
+
+    /// Find an open ticket whose subject is exactly `subject`.
+    ///
+    /// The usual dedup check before creating a ticket for a recurring alert.
+    pub async fn find_open_by_subject(
+        self,
+        subject: &str,
+    ) -> Result<Option<DetailedTicket>, Error> {
+        self.search()
+            .open()
+            .field_equals("subject", subject)
+            .first()
+            .await
+    }
+
+    /// Link many tickets to the same root-cause problem, e.g. every request
+    /// raised by one outage.
+    ///
+    /// Accepts anything yielding ticket ids, like [`TicketClient::merge`]. At most
+    /// [`BATCH_CONCURRENCY`] tickets are linked at once; each result lines up with
+    /// the ticket at the same position.
+    pub async fn associate_problem(
+        self,
+        ticket_ids: impl IntoIterator<Item = impl Into<TicketID>>,
+        problem_id: &str,
+    ) -> Vec<Result<Association, Error>> {
+        let client = self.client;
+        stream::iter(collect_ids(ticket_ids))
+            .map(|id| client.associate_problem(id, problem_id))
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// Link many tickets to the same change, see [`associate_problem`](Self::associate_problem).
+    pub async fn associate_change(
+        self,
+        ticket_ids: impl IntoIterator<Item = impl Into<TicketID>>,
+        change_id: &str,
+    ) -> Vec<Result<Association, Error>> {
+        let client = self.client;
+        stream::iter(collect_ids(ticket_ids))
+            .map(|id| client.associate_change(id, change_id))
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// Fetch every ticket assigned to the user that owns the auth token.
+    pub async fn mine(self) -> Result<Vec<DetailedTicket>, Error> {
+        self.client.my_tickets().await
+    }
+
     /// Start building a new ticket.
     pub fn create(self) -> TicketCreateBuilder<'a> {
         TicketCreateBuilder {
@@ -74,10 +149,17 @@ impl<'a> TicketsClient<'a> {
             account: None,
             template: None,
             udf_fields: None,
+            additional_fields: None,
+            mode: None,
+            request_type: None,
         }
     }
 }
 
+fn collect_ids(ids: impl IntoIterator<Item = impl Into<TicketID>>) -> Vec<TicketID> {
+    ids.into_iter().map(Into::into).collect()
+}
+
 /// Client for single ticket operations (get, close, assign, notes, merge).
 pub struct TicketClient<'a> {
     pub(crate) client: &'a ServiceDesk,
@@ -91,12 +173,22 @@ impl<'a> TicketClient<'a> {
     }
 
     /// Close the ticket with a comment.
-    pub async fn close(&self, comment: &str) -> Result<(), Error> {
+    pub async fn close(&self, comment: &str) -> Result<OperationResult, Error> {
         self.client.close_ticket(self.id, comment).await
     }
 
+    /// Start building a close request with a custom closure code.
+    pub fn close_builder(&self) -> CloseBuilder<'a> {
+        CloseBuilder {
+            client: self.client,
+            id: self.id,
+            comment: String::new(),
+            code: "Closed".to_string(),
+        }
+    }
+
     /// Assign the ticket to a technician.
-    pub async fn assign(&self, technician: &str) -> Result<(), Error> {
+    pub async fn assign(&self, technician: &str) -> Result<OperationResult, Error> {
         self.client.assign_ticket(self.id, technician).await
     }
 
@@ -104,6 +196,16 @@ impl<'a> TicketClient<'a> {
         self.client.get_conversations(self.id).await
     }
 
+    /// Overview of the ticket's conversations without fetching their content.
+    pub async fn conversation_summaries(&self) -> Result<Vec<ConversationSummary>, Error> {
+        self.client.conversation_summaries(self.id).await
+    }
+
+    /// Notes and conversations merged into one chronological transcript.
+    pub async fn thread(&self) -> Result<Vec<ThreadItem>, Error> {
+        self.client.thread(self.id).await
+    }
+
     pub async fn conversation_content(&self, content_url: &str) -> Result<Value, Error> {
         self.client.get_conversation_content(content_url).await
     }
@@ -112,28 +214,32 @@ impl<'a> TicketClient<'a> {
         self.client.add_attachment(self.id, file_path).await
     }
 
+    /// Whether the ticket itself carries attachments, cheaper than listing them.
+    pub async fn has_attachments(&self) -> Result<bool, Error> {
+        self.client.has_attachments(self.id).await
+    }
+
     /// Get all attachment links for the ticket, including conversation attachments
     /// including attachments from merged tickets.
     pub async fn all_attachment_links(&self) -> Result<Vec<String>, Error> {
-        let ticket = self.client.ticket(self.id).get().await?;
-        let mut links = Vec::new();
-        if let Some(attachments) = ticket.attachments {
-            for attachment in attachments {
-                links.push(format!(
-                    "{}{}",
-                    self.client.base_url, attachment.content_url
-                ));
-            }
-        }
-        if let Ok(attachments) = self.client.get_conversation_attachment_urls(self.id).await {
-            for url in attachments {
-                links.push(url);
-            }
-        }
-        Ok(links)
+        self.client.all_attachment_links(self.id).await
+    }
+
+    /// Number of distinct attachments, including conversation attachments.
+    pub async fn attachment_count(&self) -> Result<usize, Error> {
+        self.client.attachment_count(self.id).await
+    }
+
+    /// Stream all notes of the ticket, fetching `page_size` notes per request.
+    pub fn notes_pager(&self, page_size: u32) -> NotesPager<'a> {
+        NotesPager::new(self.client, self.id, page_size)
     }
 
     /// Add a note to the ticket with default settings.
+    ///
+    /// `description` is sent as is and rendered by SDP as HTML, so plain text
+    /// loses its line breaks; use [`note`](Self::note) with
+    /// [`NoteBuilder::plain_text`] for that.
     pub async fn add_note(&self, description: &str) -> Result<Note, Error> {
         self.client
             .add_note(
@@ -146,6 +252,16 @@ impl<'a> TicketClient<'a> {
             .await
     }
 
+    /// Add a note flagged as the first response, which SDP uses to meet the
+    /// request's first-response SLA. Returns the created note.
+    pub async fn mark_first_response(&self, note_text: &str) -> Result<Note, Error> {
+        self.note()
+            .description(note_text)
+            .mark_first_response()
+            .send()
+            .await
+    }
+
     pub async fn add_worklog(&self, worklog: &WorklogData) -> Result<Value, Error> {
         self.client.add_worklog(self.id, worklog).await
     }
@@ -160,6 +276,17 @@ impl<'a> TicketClient<'a> {
             add_to_linked_requests: false,
             notify_technician: false,
             show_to_requester: false,
+            attachment_ids: Vec::new(),
+            idempotency_key: None,
+        }
+    }
+
+    /// Start building a reply e-mail.
+    pub fn reply(&self) -> ReplyBuilder<'a> {
+        ReplyBuilder {
+            client: self.client,
+            id: self.id,
+            data: ReplyData::default(),
         }
     }
 
@@ -175,12 +302,40 @@ impl<'a> TicketClient<'a> {
             exchange_rate: None,
             mark_first_response: None,
             include_nonoperational_hours: None,
+            other_charge: None,
+            total_charge: None,
+            mark_as_chargeable: None,
         }
     }
 
     /// Merge other tickets into this one.
-    pub async fn merge(&self, ticket_ids: &[TicketID]) -> Result<(), Error> {
-        self.client.merge(self.id, ticket_ids).await
+    ///
+    /// Accepts anything yielding ticket ids, e.g. `&[u64]`, `Vec<TicketID>` or an iterator.
+    pub async fn merge(
+        &self,
+        ticket_ids: impl IntoIterator<Item = impl Into<TicketID>>,
+    ) -> Result<MergeResult, Error> {
+        self.client.merge(self.id, &collect_ids(ticket_ids)).await
+    }
+
+    /// Merge other tickets into this one, copying their notes over first.
+    pub async fn merge_preserving(
+        &self,
+        ticket_ids: impl IntoIterator<Item = impl Into<TicketID>>,
+    ) -> Result<MergeResult, Error> {
+        self.client
+            .merge_preserving(self.id, &collect_ids(ticket_ids))
+            .await
+    }
+
+    /// Link the ticket to its root-cause problem.
+    pub async fn associate_problem(&self, problem_id: &str) -> Result<Association, Error> {
+        self.client.associate_problem(self.id, problem_id).await
+    }
+
+    /// Link the ticket to a change.
+    pub async fn associate_change(&self, change_id: &str) -> Result<Association, Error> {
+        self.client.associate_change(self.id, change_id).await
     }
 
     /// List IDs of tickets that were merged into this ticket.
@@ -189,12 +344,120 @@ impl<'a> TicketClient<'a> {
     }
 
     /// Edit ticket fields.
-    pub async fn edit(&self, data: &EditTicketData) -> Result<(), Error> {
+    pub async fn edit(&self, data: &EditTicketData) -> Result<OperationResult, Error> {
         self.client.edit(self.id, data).await
     }
 
+    /// Change only the priority, by priority name.
+    pub async fn set_priority(&self, priority: &str) -> Result<(), Error> {
+        self.client.set_priority(self.id, priority).await
+    }
+
+    /// Primary account of the ticket, `None` when it has none.
+    pub async fn account(&self) -> Result<Option<Account>, Error> {
+        Ok(self.client.ticket_details(self.id).await?.account)
+    }
+
+    /// Secondary accounts the ticket is linked to.
+    pub async fn linked_to_accounts(&self) -> Result<Vec<Account>, Error> {
+        self.client.linked_to_accounts(self.id).await
+    }
+
+    /// Tags on the ticket.
+    pub async fn tags(&self) -> Result<Vec<Tag>, Error> {
+        self.client.list_tags(self.id).await
+    }
+
+    /// Add tags to the ticket.
+    pub async fn add_tags(&self, tags: &[&str]) -> Result<Vec<Tag>, Error> {
+        self.client.add_tags(self.id, tags).await
+    }
+
+    /// Remove tags from the ticket.
+    pub async fn remove_tags(&self, tags: &[&str]) -> Result<(), Error> {
+        self.client.remove_tags(self.id, tags).await
+    }
+
+    /// Get the SLA applied to the ticket.
+    pub async fn sla(&self) -> Result<Sla, Error> {
+        self.client.get_sla(self.id).await
+    }
+
+    /// Summarize the ticket's approvals, e.g. to check whether it is approved yet.
+    pub async fn approval_status(&self) -> Result<ApprovalStatus, Error> {
+        self.client.approval_status(self.id).await
+    }
+
+    /// Move the ticket to another site and/or group, running SDP's move workflow.
+    pub async fn move_to(&self, site: Option<&str>, group: Option<&str>) -> Result<(), Error> {
+        self.client.move_request(self.id, site, group).await
+    }
+
+    /// Raise the priority one level, capped at Critical, returning the new priority.
+    pub async fn escalate_priority(&self) -> Result<Priority, Error> {
+        self.client.escalate_priority(self.id).await
+    }
+
+    /// Change the request type, e.g. to "Service Request".
+    pub async fn reclassify(&self, request_type: &str) -> Result<(), Error> {
+        self.client.reclassify(self.id, request_type).await
+    }
+
+    /// Move the ticket back to Open.
+    pub async fn reopen(&self) -> Result<(), Error> {
+        self.client.reopen_ticket(self.id).await
+    }
+
+    /// Move the ticket back to Open, bypassing closure rules where the instance allows it.
+    pub async fn reopen_forced(&self) -> Result<(), Error> {
+        self.client.reopen_forced(self.id).await
+    }
+
+    /// Add a note, then reopen the ticket.
+    ///
+    /// The two calls are not transactional, if reopening fails the note stays posted.
+    pub async fn reopen_with_note(&self, comment: &str) -> Result<(), Error> {
+        self.add_note(comment).await?;
+        self.client.reopen_ticket(self.id).await
+    }
+
+    /// Escalation runbook: reopen the ticket if it is closed or resolved, assign
+    /// it to `technician`, then add `note` as an internal note.
+    ///
+    /// The steps run one after another and are not transactional. The first
+    /// failing step's error is returned, the steps before it stay applied.
+    pub async fn escalate(&self, technician: &str, note: &str) -> Result<(), Error> {
+        let status = self.get().await?.status;
+        if ["Closed", "Resolved"]
+            .iter()
+            .any(|name| status.name.eq_ignore_ascii_case(name))
+        {
+            self.reopen().await?;
+        }
+        self.assign(technician).await?;
+        self.add_note(note).await?;
+        Ok(())
+    }
+
+    /// Set the resolution, then close the ticket with a closure code.
+    ///
+    /// Avoids the closure-rule violation (4003) on instances where a resolution
+    /// is mandatory for closing. These are two calls under the hood, not a
+    /// transaction: if closing fails the resolution stays set.
+    pub async fn resolve_and_close(
+        &self,
+        resolution: &str,
+        closure_comments: &str,
+        code: &str,
+    ) -> Result<OperationResult, Error> {
+        self.client.add_resolution(self.id, resolution).await?;
+        self.client
+            .close_with_code(self.id, closure_comments, code)
+            .await
+    }
+
     /// Close ticket with a note.
-    pub async fn close_with_note(&self, comment: &str) -> Result<(), Error> {
+    pub async fn close_with_note(&self, comment: &str) -> Result<OperationResult, Error> {
         self.client
             .add_note(
                 self.id,
@@ -208,6 +471,50 @@ impl<'a> TicketClient<'a> {
     }
 }
 
+/// Stream of a ticket's notes, fetched lazily one page at a time.
+///
+/// The next page is only requested once the current one is consumed and
+/// SDP reported `has_more_rows`. The stream ends after the first error.
+pub struct NotesPager<'a> {
+    inner: Pin<Box<dyn Stream<Item = Result<Note, Error>> + Send + 'a>>,
+}
+
+impl<'a> NotesPager<'a> {
+    fn new(client: &'a ServiceDesk, id: TicketID, page_size: u32) -> Self {
+        let page_size = page_size.max(1);
+        let pages = stream::unfold(Some(1), move |start_index| async move {
+            let start_index = start_index?;
+            match client.list_notes_page(id, page_size, start_index).await {
+                Ok(page) => {
+                    let has_more = page.list_info.is_some_and(|info| info.has_more_rows)
+                        && !page.notes.is_empty();
+                    let next = has_more.then(|| start_index + page_size);
+                    Some((Ok(page.notes), next))
+                }
+                Err(e) => Some((Err(e), None)),
+            }
+        });
+        let notes = pages.flat_map(|page| {
+            let items: Vec<Result<Note, Error>> = match page {
+                Ok(notes) => notes.into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(e)],
+            };
+            stream::iter(items)
+        });
+        NotesPager {
+            inner: Box::pin(notes),
+        }
+    }
+}
+
+impl Stream for NotesPager<'_> {
+    type Item = Result<Note, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
 /// Builder for searching tickets.
 ///
 /// All filter methods are optional. Default limit is 100 results.
@@ -216,6 +523,21 @@ pub struct TicketSearchBuilder<'a> {
     root_criteria: Option<Criteria>,
     children: Vec<Criteria>,
     row_count: u32,
+    include_trashed: bool,
+}
+
+/// `list_info` of a ticket search, with the trash flag next to the usual fields.
+#[derive(Serialize, Debug)]
+struct TicketListInfo {
+    #[serde(flatten)]
+    list_info: ListInfo,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    include_trashed: bool,
+}
+
+#[derive(Serialize, Debug)]
+struct TicketSearchRequest {
+    list_info: TicketListInfo,
 }
 
 /// Ticket status filter values.
@@ -239,6 +561,37 @@ impl std::fmt::Display for TicketStatus {
     }
 }
 
+/// Standard SDP closure codes, for [`CloseBuilder::code`] and
+/// [`ServiceDesk::close_with_code`] (via `to_string()`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClosureCodeKind {
+    Closed,
+    Resolved,
+    Cancelled,
+    PostponedToFutureRelease,
+    /// A closure code configured on the instance, sent as is.
+    Custom(String),
+}
+
+impl std::fmt::Display for ClosureCodeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let code = match self {
+            ClosureCodeKind::Closed => "Closed",
+            ClosureCodeKind::Resolved => "Resolved",
+            ClosureCodeKind::Cancelled => "Cancelled",
+            ClosureCodeKind::PostponedToFutureRelease => "Postponed to future release",
+            ClosureCodeKind::Custom(code) => code,
+        };
+        write!(f, "{}", code)
+    }
+}
+
+impl From<ClosureCodeKind> for String {
+    fn from(value: ClosureCodeKind) -> Self {
+        value.to_string()
+    }
+}
+
 impl<'a> TicketSearchBuilder<'a> {
     /// Filter by ticket status.
     pub fn status(mut self, status: &str) -> Self {
@@ -268,22 +621,31 @@ impl<'a> TicketSearchBuilder<'a> {
     }
 
     /// Filter tickets created after a given time.
-    pub fn created_after(mut self, time: DateTime<Local>) -> Self {
-        self.children.push(Criteria {
-            field: "created_time".to_string(),
-            condition: Condition::GreaterThan,
-            value: time.timestamp_millis().to_string().into(),
-            children: vec![],
-            logical_operator: Some(LogicalOp::And),
-        });
-        self
+    pub fn created_after(self, time: DateTime<Local>) -> Self {
+        self.field_date_after("created_time", time.with_timezone(&Utc))
     }
 
     /// Filter tickets last updated after a given time.
-    pub fn updated_after(mut self, time: DateTime<Local>) -> Self {
+    pub fn updated_after(self, time: DateTime<Local>) -> Self {
+        self.field_date_after("last_updated_time", time.with_timezone(&Utc))
+    }
+
+    /// Filter by any date field being after `time`, e.g. `"due_by_time"`.
+    ///
+    /// SDP compares date fields against epoch millis sent as a string.
+    pub fn field_date_after(self, field: &str, time: DateTime<Utc>) -> Self {
+        self.field_date(field, Condition::GreaterThan, time)
+    }
+
+    /// Filter by any date field being before `time`.
+    pub fn field_date_before(self, field: &str, time: DateTime<Utc>) -> Self {
+        self.field_date(field, Condition::LesserThan, time)
+    }
+
+    fn field_date(mut self, field: &str, condition: Condition, time: DateTime<Utc>) -> Self {
         self.children.push(Criteria {
-            field: "last_updated_time".to_string(),
-            condition: Condition::GreaterThan,
+            field: field.to_string(),
+            condition,
             value: time.timestamp_millis().to_string().into(),
             children: vec![],
             logical_operator: Some(LogicalOp::And),
@@ -291,6 +653,18 @@ impl<'a> TicketSearchBuilder<'a> {
         self
     }
 
+    /// Filter tickets created within the last `window`, e.g. the last hour.
+    pub fn created_in_last(self, window: Duration) -> Self {
+        let since = time_before(self.client.now().with_timezone(&Local), window);
+        self.created_after(since)
+    }
+
+    /// Filter tickets last updated within the last `window`.
+    pub fn updated_in_last(self, window: Duration) -> Self {
+        let since = time_before(self.client.now().with_timezone(&Local), window);
+        self.updated_after(since)
+    }
+
     /// Filter by subject containing a value.
     pub fn subject_contains(mut self, value: &str) -> Self {
         self.children.push(Criteria {
@@ -303,6 +677,36 @@ impl<'a> TicketSearchBuilder<'a> {
         self
     }
 
+    /// Filter by the technician group the ticket is assigned to.
+    pub fn group_is(mut self, name: &str) -> Self {
+        self.children.push(Criteria {
+            field: "group.name".to_string(),
+            condition: Condition::Is,
+            value: name.into(),
+            children: vec![],
+            logical_operator: Some(LogicalOp::And),
+        });
+        self
+    }
+
+    /// Filter by requester, matched on `requester.email_id` when `name_or_email`
+    /// contains `@` and on `requester.name` otherwise.
+    pub fn requester_is(mut self, name_or_email: &str) -> Self {
+        let field = if name_or_email.contains('@') {
+            "requester.email_id"
+        } else {
+            "requester.name"
+        };
+        self.children.push(Criteria {
+            field: field.to_string(),
+            condition: Condition::Is,
+            value: name_or_email.into(),
+            children: vec![],
+            logical_operator: Some(LogicalOp::And),
+        });
+        self
+    }
+
     /// Filter by a custom field containing a value.
     pub fn field_contains(mut self, field: &str, value: impl Into<Value>) -> Self {
         self.children.push(Criteria {
@@ -343,8 +747,19 @@ impl<'a> TicketSearchBuilder<'a> {
         self
     }
 
-    /// Execute the search and return results.
-    pub async fn fetch(self) -> Result<Vec<DetailedTicket>, Error> {
+    /// Also return requests sitting in the trash, e.g. for audit exports.
+    ///
+    /// SDP leaves trashed requests out of searches unless `list_info` carries
+    /// `"include_trashed": true`, which this sets. Builds that don't know the
+    /// field ignore it and keep excluding trash; trashed results are marked by
+    /// [`DetailedTicket::is_deleted`].
+    pub fn include_trashed(mut self) -> Self {
+        self.include_trashed = true;
+        self
+    }
+
+    /// Combine the filters into the root criteria sent to SDP.
+    fn search_criteria(self) -> Criteria {
         let mut root = self.root_criteria.unwrap_or_else(|| Criteria {
             field: "id".to_string(),
             condition: Condition::GreaterThan,
@@ -353,22 +768,97 @@ impl<'a> TicketSearchBuilder<'a> {
             logical_operator: None,
         });
 
-        root.children = self.children;
+        root.children.extend(self.children);
+        root
+    }
 
-        let body = SearchRequest {
-            list_info: ListInfo {
-                row_count: self.row_count,
-                search_criteria: root,
-            },
+    async fn send_search(
+        client: &ServiceDesk,
+        list_info: ListInfo,
+        include_trashed: bool,
+    ) -> Result<TicketSearchResponse, Error> {
+        let resp: Value = client
+            .request_input_data(
+                Method::GET,
+                "/api/v3/requests",
+                &TicketSearchRequest {
+                    list_info: TicketListInfo {
+                        list_info,
+                        include_trashed,
+                    },
+                },
+            )
+            .await?;
+
+        Ok(serde_json::from_value(resp)?)
+    }
+
+    /// Execute the search and return results.
+    pub async fn fetch(self) -> Result<Vec<DetailedTicket>, Error> {
+        let client = self.client;
+        let include_trashed = self.include_trashed;
+        let list_info = ListInfo {
+            row_count: self.row_count,
+            start_index: None,
+            get_total_count: None,
+            search_criteria: self.search_criteria(),
         };
 
-        let resp: Value = self
-            .client
-            .request_input_data(Method::GET, "/api/v3/requests", &body)
-            .await?;
+        Ok(Self::send_search(client, list_info, include_trashed)
+            .await?
+            .requests)
+    }
+
+    /// Fetch a single page starting at `start_index` (1-based), along with
+    /// the total match count and whether more rows remain.
+    ///
+    /// The page size is the configured [`limit`](Self::limit).
+    pub async fn fetch_page(self, start_index: u32) -> Result<SearchPage, Error> {
+        let client = self.client;
+        let include_trashed = self.include_trashed;
+        let list_info = ListInfo {
+            row_count: self.row_count,
+            start_index: Some(start_index),
+            get_total_count: Some(true),
+            search_criteria: self.search_criteria(),
+        };
 
-        let ticket_response: TicketSearchResponse = serde_json::from_value(resp)?;
-        Ok(ticket_response.requests)
+        let resp = Self::send_search(client, list_info, include_trashed).await?;
+        let info = resp.list_info.unwrap_or_default();
+        Ok(SearchPage {
+            tickets: resp.requests,
+            total_count: info.total_count,
+            has_more_rows: info.has_more_rows,
+            start_index,
+        })
+    }
+
+    /// Execute the search page by page and return every match.
+    ///
+    /// The page size is the configured [`limit`](Self::limit).
+    pub async fn fetch_all(self) -> Result<Vec<DetailedTicket>, Error> {
+        let client = self.client;
+        let include_trashed = self.include_trashed;
+        let row_count = self.row_count.max(1);
+        let criteria = self.search_criteria();
+        let mut tickets = Vec::new();
+        let mut start_index = 1;
+        loop {
+            let list_info = ListInfo {
+                row_count,
+                start_index: Some(start_index),
+                get_total_count: None,
+                search_criteria: criteria.clone(),
+            };
+            let resp = Self::send_search(client, list_info, include_trashed).await?;
+            let has_more_rows = resp.list_info.is_some_and(|info| info.has_more_rows);
+            let received = resp.requests.len() as u32;
+            tickets.extend(resp.requests);
+            if !has_more_rows || received == 0 {
+                return Ok(tickets);
+            }
+            start_index += received;
+        }
     }
 
     /// Execute the search and return the first result.
@@ -392,6 +882,9 @@ pub struct TicketCreateBuilder<'a> {
     account: Option<String>,
     template: Option<String>,
     udf_fields: Option<Value>,
+    additional_fields: Option<Value>,
+    mode: Option<String>,
+    request_type: Option<String>,
 }
 
 impl<'a> TicketCreateBuilder<'a> {
@@ -401,6 +894,18 @@ impl<'a> TicketCreateBuilder<'a> {
         self
     }
 
+    /// Set the subject from a template with `{key}` placeholders.
+    ///
+    /// Returns [`Error::InvalidValue`] if a placeholder has no matching entry in `vars`.
+    pub fn subject_template(
+        self,
+        template: &str,
+        vars: &HashMap<&str, &str>,
+    ) -> Result<Self, Error> {
+        let subject = render_template(template, vars)?;
+        Ok(self.subject(subject))
+    }
+
     /// Set the ticket description.
     pub fn description(mut self, description: impl Into<String>) -> Self {
         self.description = Some(description.into());
@@ -425,6 +930,48 @@ impl<'a> TicketCreateBuilder<'a> {
         self
     }
 
+    /// Start from the request template `name`, fetched with
+    /// [`ServiceDesk::get_template`].
+    ///
+    /// Applies the template's default subject, description, priority and UDFs,
+    /// and adds a `null` placeholder for each mandatory UDF without a default,
+    /// leaving only those to fill in. Call it first: later
+    /// setters override the template, but note that [`udf_fields`](Self::udf_fields)
+    /// replaces all UDFs, placeholders included.
+    pub async fn from_template(mut self, name: &str) -> Result<Self, Error> {
+        let template = self.client.get_template(name).await?;
+        let defaults = &template.defaults;
+        let text = |key: &str| {
+            defaults
+                .get(key)
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        };
+        if let Some(subject) = text("subject") {
+            self.subject = Some(subject);
+        }
+        if let Some(description) = text("description") {
+            self.description = Some(description);
+        }
+        if let Some(priority) = defaults
+            .get("priority")
+            .and_then(|p| serde_json::from_value::<Priority>(p.clone()).ok())
+        {
+            self.priority = priority;
+        }
+        let mut udfs = defaults
+            .get("udf_fields")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+        for field in template.udf_fields.iter().filter(|f| f.mandatory) {
+            udfs.entry(field.key.clone()).or_insert(Value::Null);
+        }
+        self.udf_fields = Some(Value::Object(udfs));
+        self.template = Some(template.name);
+        Ok(self)
+    }
+
     /// Set the template name.
     pub fn template(mut self, template: impl Into<String>) -> Self {
         self.template = Some(template.into());
@@ -437,8 +984,26 @@ impl<'a> TicketCreateBuilder<'a> {
         self
     }
 
-    /// Create the ticket.
-    pub async fn send(self) -> Result<TicketData, Error> {
+    /// Set request-level custom fields kept apart from UDFs on some instances.
+    pub fn additional_fields(mut self, fields: Value) -> Self {
+        self.additional_fields = Some(fields);
+        self
+    }
+
+    /// Set the request mode, e.g. "E-Mail" or "Web Form".
+    pub fn mode(mut self, mode: impl Into<String>) -> Self {
+        self.mode = Some(mode.into());
+        self
+    }
+
+    /// Set the request type, e.g. "Incident" or "Service Request".
+    pub fn request_type(mut self, request_type: impl Into<String>) -> Self {
+        self.request_type = Some(request_type.into());
+        self
+    }
+
+    /// Build the raw [`CreateTicketData`] without sending it.
+    pub fn build(self) -> Result<CreateTicketData, Error> {
         let subject = self
             .subject
             .ok_or_else(|| Error::Other("subject is required".to_string()))?;
@@ -446,7 +1011,7 @@ impl<'a> TicketCreateBuilder<'a> {
             .requester
             .ok_or_else(|| Error::Other("requester is required".to_string()))?;
 
-        let data = CreateTicketData {
+        Ok(CreateTicketData {
             subject,
             description: self.description.unwrap_or_default(),
             requester,
@@ -454,10 +1019,80 @@ impl<'a> TicketCreateBuilder<'a> {
             account: self.account.unwrap_or_default(),
             template: self.template.unwrap_or_default(),
             udf_fields: self.udf_fields.unwrap_or(serde_json::json!({})),
+            additional_fields: self.additional_fields,
+            mode: self.mode,
+            request_type: self.request_type,
+        })
+    }
+
+    /// Create the ticket.
+    pub async fn send(self) -> Result<TicketData, Error> {
+        let client = self.client;
+        let data = self.build()?;
+        client.create_ticket(&data).await
+    }
+
+    /// Validate the UDF fields against `template` and create the ticket.
+    ///
+    /// See [`CreateTicketData::validate_against`].
+    pub async fn send_validated(self, template: &Template) -> Result<TicketData, Error> {
+        let client = self.client;
+        let data = self.build()?;
+        data.validate_against(template)?;
+        client.create_ticket(&data).await
+    }
+}
+
+/// `now - window`, saturating at the earliest representable time.
+fn time_before(now: DateTime<Local>, window: Duration) -> DateTime<Local> {
+    chrono::TimeDelta::from_std(window)
+        .ok()
+        .and_then(|window| now.checked_sub_signed(window))
+        .unwrap_or_else(|| DateTime::<chrono::Utc>::MIN_UTC.with_timezone(&Local))
+}
+
+/// Substitute `{key}` placeholders in `template` with values from `vars`.
+/// An unclosed `{` is kept as-is.
+fn render_template(template: &str, vars: &HashMap<&str, &str>) -> Result<String, Error> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            out.push_str(&rest[start..]);
+            return Ok(out);
         };
+        let key = &after[..end];
+        let value = vars.get(key).ok_or_else(|| {
+            Error::InvalidValue(format!("unresolved subject placeholder: {{{key}}}"))
+        })?;
+        out.push_str(value);
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
 
-        self.client.create_ticket(&data).await
+/// Escape `text` for HTML and turn its line breaks (`\n` or `\r\n`) into `<br>`.
+fn plain_text_to_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            out.push_str("<br>");
+        }
+        for c in line.strip_suffix('\r').unwrap_or(line).chars() {
+            match c {
+                '&' => out.push_str("&amp;"),
+                '<' => out.push_str("&lt;"),
+                '>' => out.push_str("&gt;"),
+                '"' => out.push_str("&quot;"),
+                '\'' => out.push_str("&#39;"),
+                c => out.push(c),
+            }
+        }
     }
+    out
 }
 
 /// Builder for adding notes with custom settings.
@@ -471,15 +1106,30 @@ pub struct NoteBuilder<'a> {
     add_to_linked_requests: bool,
     notify_technician: bool,
     show_to_requester: bool,
+    attachment_ids: Vec<String>,
+    idempotency_key: Option<String>,
 }
 
 impl<'a> NoteBuilder<'a> {
-    /// Set the note content.
+    /// Set the note content, sent as is. Same as [`html`](Self::html).
     pub fn description(mut self, description: impl Into<String>) -> Self {
         self.description = description.into();
         self
     }
 
+    /// Set the note content from plain text: HTML special characters are
+    /// escaped and newlines become `<br>`.
+    pub fn plain_text(mut self, text: &str) -> Self {
+        self.description = plain_text_to_html(text);
+        self
+    }
+
+    /// Set the note content as raw HTML.
+    pub fn html(mut self, html: impl Into<String>) -> Self {
+        self.description = html.into();
+        self
+    }
+
     /// Mark as first response.
     pub fn mark_first_response(mut self) -> Self {
         self.mark_first_response = true;
@@ -504,7 +1154,25 @@ impl<'a> NoteBuilder<'a> {
         self
     }
 
+    /// Attach a file previously uploaded to SDP, by its file id.
+    pub fn attach(mut self, file_id: impl Into<String>) -> Self {
+        self.attachment_ids.push(file_id.into());
+        self
+    }
+
+    /// Skip posting if a recent note already carries this idempotency key.
+    ///
+    /// Makes retries of [`send`](Self::send) safe, see
+    /// [`ServiceDesk::add_note_idempotent`] for how the key is stored and the
+    /// limitations of the check.
+    pub fn idempotent(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
     /// Build the raw [`NoteData`] without sending it.
+    ///
+    /// The idempotency key, if any, is only applied by [`send`](Self::send).
     pub fn build(self) -> NoteData {
         NoteData {
             description: self.description,
@@ -512,6 +1180,7 @@ impl<'a> NoteBuilder<'a> {
             add_to_linked_requests: self.add_to_linked_requests,
             notify_technician: self.notify_technician,
             show_to_requester: self.show_to_requester,
+            attachment_ids: self.attachment_ids,
         }
     }
 
@@ -519,8 +1188,148 @@ impl<'a> NoteBuilder<'a> {
     pub async fn send(self) -> Result<Note, Error> {
         let client = self.client;
         let id = self.id;
+        let key = self.idempotency_key.clone();
         let note = self.build();
-        client.add_note(id, &note).await
+        match key {
+            Some(key) => client.add_note_idempotent(id, &note, &key).await,
+            None => client.add_note(id, &note).await,
+        }
+    }
+}
+
+/// Builder for a reply e-mail sent from a ticket.
+pub struct ReplyBuilder<'a> {
+    client: &'a ServiceDesk,
+    id: TicketID,
+    data: ReplyData,
+}
+
+impl<'a> ReplyBuilder<'a> {
+    /// Add a recipient e-mail address.
+    pub fn to(mut self, email: impl Into<String>) -> Self {
+        self.data.to.push(email.into());
+        self
+    }
+
+    /// Add a CC e-mail address.
+    pub fn cc(mut self, email: impl Into<String>) -> Self {
+        self.data.cc.push(email.into());
+        self
+    }
+
+    /// Set the e-mail subject.
+    pub fn subject(mut self, subject: impl Into<String>) -> Self {
+        self.data.subject = subject.into();
+        self
+    }
+
+    /// Set the e-mail body.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.data.description = description.into();
+        self
+    }
+
+    /// Attach a file previously uploaded to SDP, by its file id.
+    pub fn attach(mut self, file_id: impl Into<String>) -> Self {
+        self.data.attachment_ids.push(file_id.into());
+        self
+    }
+
+    /// Build the raw [`ReplyData`] without sending it.
+    pub fn build(self) -> ReplyData {
+        self.data
+    }
+
+    /// Send the reply.
+    pub async fn send(self) -> Result<(), Error> {
+        self.client.reply(self.id, &self.data).await
+    }
+}
+
+/// Builder for closing a ticket with a closure code.
+///
+/// The closure code defaults to "Closed".
+pub struct CloseBuilder<'a> {
+    client: &'a ServiceDesk,
+    id: TicketID,
+    comment: String,
+    code: String,
+}
+
+impl<'a> CloseBuilder<'a> {
+    /// Set the closure comment.
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = comment.into();
+        self
+    }
+
+    /// Set the closure code without checking it against the instance.
+    pub fn code(mut self, code: impl Into<String>) -> Self {
+        self.code = code.into();
+        self
+    }
+
+    /// Set the closure code after checking that it is configured on the instance.
+    ///
+    /// Fetches [`ServiceDesk::list_closure_codes`] and returns
+    /// [`Error::InvalidValue`] before anything is sent if `name` isn't in it.
+    pub async fn code_checked(mut self, name: &str) -> Result<Self, Error> {
+        let codes = self.client.list_closure_codes().await?;
+        let code = codes
+            .into_iter()
+            .find(|code| code.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| Error::InvalidValue(format!("unknown closure code: {name}")))?;
+        self.code = code.name;
+        Ok(self)
+    }
+
+    /// Close the ticket.
+    pub async fn send(self) -> Result<OperationResult, Error> {
+        self.client
+            .close_with_code(self.id, &self.comment, &self.code)
+            .await
+    }
+}
+
+/// Builder permanently deleting trashed tickets, guarded against accidental
+/// mass deletion.
+///
+/// The caller has to restate how many tickets they expect to delete; a
+/// mismatch, or a missing confirmation, fails before anything is sent. SDP
+/// answers with [`Error::Sdp`] code 4017 (not in trash) if an id isn't trashed.
+pub struct EmptyTrashBuilder<'a> {
+    client: &'a ServiceDesk,
+    ids: Vec<TicketID>,
+    confirmed: Option<usize>,
+}
+
+impl<'a> EmptyTrashBuilder<'a> {
+    /// Confirm the number of tickets about to be deleted.
+    pub fn confirm(mut self, expected_count: usize) -> Self {
+        self.confirmed = Some(expected_count);
+        self
+    }
+
+    /// Permanently delete the tickets.
+    ///
+    /// Returns [`Error::InvalidValue`] if the confirmed count is missing or
+    /// differs from the number of ids.
+    pub async fn send(self) -> Result<(), Error> {
+        match self.confirmed {
+            Some(expected) if expected == self.ids.len() => {}
+            Some(expected) => {
+                return Err(Error::InvalidValue(format!(
+                    "confirmed {expected} deletions but {} ids were given",
+                    self.ids.len()
+                )));
+            }
+            None => {
+                return Err(Error::InvalidValue(
+                    "deleting from trash requires confirm(expected_count)".into(),
+                ));
+            }
+        }
+        self.client.empty_trash(&self.ids).await
     }
 }
 
@@ -536,11 +1345,20 @@ pub struct WorklogData {
     exchange_rate: Option<f64>,
     mark_first_response: bool,
     include_nonoperational_hours: bool,
+    /// Charges on top of the time spent, e.g. travel or materials
+    #[serde(skip_serializing_if = "Option::is_none")]
+    other_charge: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_charge: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mark_as_chargeable: Option<bool>,
 }
 
-fn serialize_sdp_time<S>(dt: &DateTime<Local>, serializer: S) -> Result<S::Ok, S::Error>
+/// Serialize a timestamp as SDP's `{ "value": <epoch millis> }` time object.
+pub(crate) fn serialize_sdp_time<S, Tz>(dt: &DateTime<Tz>, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
+    Tz: chrono::TimeZone,
 {
     use serde::ser::SerializeStruct;
     let mut s = serializer.serialize_struct("SdpTime", 1)?;
@@ -548,6 +1366,20 @@ where
     s.end()
 }
 
+pub(crate) fn serialize_optional_sdp_time<S, Tz>(
+    dt: &Option<DateTime<Tz>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    Tz: chrono::TimeZone,
+{
+    match dt {
+        Some(dt) => serialize_sdp_time(dt, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
 pub struct WorklogBuilder<'a> {
     client: &'a ServiceDesk,
     id: TicketID,
@@ -558,6 +1390,9 @@ pub struct WorklogBuilder<'a> {
     exchange_rate: Option<f64>,
     mark_first_response: Option<bool>,
     include_nonoperational_hours: Option<bool>,
+    other_charge: Option<f64>,
+    total_charge: Option<f64>,
+    mark_as_chargeable: Option<bool>,
 }
 
 impl<'a> WorklogBuilder<'a> {
@@ -602,6 +1437,24 @@ impl<'a> WorklogBuilder<'a> {
         self
     }
 
+    /// Set charges on top of the time spent, e.g. travel or materials.
+    pub fn other_charge(mut self, other_charge: f64) -> Self {
+        self.other_charge = Some(other_charge);
+        self
+    }
+
+    /// Set the total charge, overriding SDP's computed value.
+    pub fn total_charge(mut self, total_charge: f64) -> Self {
+        self.total_charge = Some(total_charge);
+        self
+    }
+
+    /// Mark the worklog as chargeable to the requester.
+    pub fn mark_as_chargeable(mut self) -> Self {
+        self.mark_as_chargeable = Some(true);
+        self
+    }
+
     /// Build the raw [`WorklogData`] without sending it.
     pub fn build(self) -> Result<WorklogData, Error> {
         Ok(WorklogData {
@@ -614,6 +1467,9 @@ impl<'a> WorklogBuilder<'a> {
             exchange_rate: self.exchange_rate,
             mark_first_response: self.mark_first_response.unwrap_or(false),
             include_nonoperational_hours: self.include_nonoperational_hours.unwrap_or(false),
+            other_charge: self.other_charge,
+            total_charge: self.total_charge,
+            mark_as_chargeable: self.mark_as_chargeable,
         })
     }
 
@@ -652,4 +1508,248 @@ mod tests {
         assert_eq!(TicketStatus::Cancelled.to_string(), "Cancelled");
         assert_eq!(TicketStatus::OnHold.to_string(), "On Hold");
     }
+
+    #[test]
+    fn render_template_substitutes_vars() {
+        let vars = HashMap::from([("account", "NETXP"), ("name", "Brute force")]);
+        let subject = render_template("[{account}] Alert: {name}", &vars).unwrap();
+        assert_eq!(subject, "[NETXP] Alert: Brute force");
+    }
+
+    #[test]
+    fn render_template_rejects_missing_vars() {
+        let vars = HashMap::from([("account", "NETXP")]);
+        let err = render_template("[{account}] Alert: {name}", &vars).unwrap_err();
+        assert!(matches!(err, Error::InvalidValue(msg) if msg.contains("{name}")));
+    }
+
+    fn worklog_data(
+        other_charge: Option<f64>,
+        total_charge: Option<f64>,
+        mark_as_chargeable: Option<bool>,
+    ) -> WorklogData {
+        let time = DateTime::from_timestamp_millis(1_700_000_000_000)
+            .unwrap()
+            .with_timezone(&Local);
+        WorklogData {
+            owner: UserInfo::default(),
+            description: String::new(),
+            start_time: time,
+            end_time: time,
+            exchange_rate: None,
+            mark_first_response: false,
+            include_nonoperational_hours: false,
+            other_charge,
+            total_charge,
+            mark_as_chargeable,
+        }
+    }
+
+    #[test]
+    fn worklog_serializes_charge_fields() {
+        let value = serde_json::to_value(worklog_data(Some(12.5), Some(80.0), Some(true))).unwrap();
+
+        assert_eq!(value["other_charge"], serde_json::json!(12.5));
+        assert_eq!(value["total_charge"], serde_json::json!(80.0));
+        assert_eq!(value["mark_as_chargeable"], serde_json::json!(true));
+    }
+
+    #[test]
+    fn worklog_omits_unset_charge_fields() {
+        let value = serde_json::to_value(worklog_data(None, None, None)).unwrap();
+
+        assert!(value.get("other_charge").is_none());
+        assert!(value.get("total_charge").is_none());
+        assert!(value.get("mark_as_chargeable").is_none());
+    }
+
+    #[test]
+    fn time_before_subtracts_window() {
+        let now = DateTime::from_timestamp_millis(1_700_000_000_000)
+            .unwrap()
+            .with_timezone(&Local);
+
+        let since = time_before(now, Duration::from_secs(3600));
+
+        assert_eq!(since.timestamp_millis(), 1_700_000_000_000 - 3_600_000);
+    }
+
+    #[test]
+    fn time_before_saturates_on_huge_window() {
+        let now = DateTime::from_timestamp_millis(1_700_000_000_000)
+            .unwrap()
+            .with_timezone(&Local);
+
+        let since = time_before(now, Duration::MAX);
+
+        assert_eq!(
+            since,
+            DateTime::<chrono::Utc>::MIN_UTC.with_timezone(&Local)
+        );
+    }
+
+    fn test_client() -> ServiceDesk {
+        ServiceDesk::new(
+            reqwest::Url::parse("https://sdp.example.com").unwrap(),
+            crate::Credentials::Token {
+                token: "token".into(),
+            },
+            crate::ServiceDeskOptions::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn closure_code_kind_display() {
+        assert_eq!(ClosureCodeKind::Closed.to_string(), "Closed");
+        assert_eq!(ClosureCodeKind::Resolved.to_string(), "Resolved");
+        assert_eq!(ClosureCodeKind::Cancelled.to_string(), "Cancelled");
+        assert_eq!(
+            ClosureCodeKind::PostponedToFutureRelease.to_string(),
+            "Postponed to future release"
+        );
+        assert_eq!(
+            ClosureCodeKind::Custom("Duplicate".into()).to_string(),
+            "Duplicate"
+        );
+
+        let client = test_client();
+        let builder = client.ticket(1).close_builder();
+        assert_eq!(builder.code(ClosureCodeKind::Resolved).code, "Resolved");
+    }
+
+    #[test]
+    fn field_date_filters_serialize_as_epoch_millis() {
+        let client = test_client();
+        let time = DateTime::from_timestamp_millis(1_700_000_000_000).unwrap();
+
+        let criteria = client
+            .tickets()
+            .search()
+            .field_date_after("due_by_time", time)
+            .field_date_before("resolved_time", time)
+            .search_criteria();
+
+        assert_eq!(
+            serde_json::to_value(&criteria.children).unwrap(),
+            serde_json::json!([
+                {
+                    "field": "due_by_time",
+                    "condition": "greater than",
+                    "value": "1700000000000",
+                    "logical_operator": "AND",
+                },
+                {
+                    "field": "resolved_time",
+                    "condition": "lesser than",
+                    "value": "1700000000000",
+                    "logical_operator": "AND",
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn group_is_filters_on_group_name() {
+        let client = test_client();
+
+        let criteria = client
+            .tickets()
+            .search()
+            .open()
+            .group_is("Network")
+            .search_criteria();
+
+        assert_eq!(
+            serde_json::to_value(&criteria).unwrap(),
+            serde_json::json!({
+                "field": "status.name",
+                "condition": "is",
+                "value": "Open",
+                "children": [{
+                    "field": "group.name",
+                    "condition": "is",
+                    "value": "Network",
+                    "logical_operator": "AND",
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn created_after_matches_field_date_after() {
+        let client = test_client();
+        let time = DateTime::from_timestamp_millis(1_700_000_000_000).unwrap();
+
+        let created = client
+            .tickets()
+            .search()
+            .created_after(time.with_timezone(&Local))
+            .search_criteria();
+        let generic = client
+            .tickets()
+            .search()
+            .field_date_after("created_time", time)
+            .search_criteria();
+
+        assert_eq!(created, generic);
+    }
+
+    #[test]
+    fn note_builder_serializes_attachments_as_id_refs() {
+        let client = test_client();
+
+        let with = client
+            .ticket(1)
+            .note()
+            .description("see logs")
+            .attach("901")
+            .attach("902")
+            .build();
+        let without = client.ticket(1).note().build();
+
+        assert_eq!(
+            serde_json::to_value(&with).unwrap()["attachments"],
+            serde_json::json!([{ "id": "901" }, { "id": "902" }])
+        );
+        assert!(
+            serde_json::to_value(&without)
+                .unwrap()
+                .get("attachments")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn collect_ids_accepts_common_id_collections() {
+        let expected = vec![TicketID(1), TicketID(2)];
+
+        let raw: &[u64] = &[1, 2];
+        assert_eq!(collect_ids(raw), expected);
+        assert_eq!(collect_ids([1u64, 2]), expected);
+        assert_eq!(collect_ids(vec![TicketID(1), TicketID(2)]), expected);
+        assert_eq!(collect_ids(&expected), expected);
+        assert_eq!(collect_ids(&expected[..]), expected);
+        assert_eq!(collect_ids(1u64..=2), expected);
+    }
+
+    #[test]
+    fn plain_text_notes_are_escaped_with_line_breaks() {
+        assert_eq!(
+            plain_text_to_html("Disk <90%> & \"full\"\r\nsee 'df'\n\ndone"),
+            "Disk &lt;90%&gt; &amp; &quot;full&quot;<br>see &#39;df&#39;<br><br>done"
+        );
+        assert_eq!(plain_text_to_html("\nlast\n"), "<br>last<br>");
+
+        let client = test_client();
+        let ticket = client.ticket(1);
+        assert_eq!(
+            ticket.note().plain_text("a\nb").build().description,
+            "a<br>b"
+        );
+        assert_eq!(
+            ticket.note().html("<b>a</b>").build().description,
+            "<b>a</b>"
+        );
+    }
 }
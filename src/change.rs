@@ -0,0 +1,141 @@
+//! Changes module (`/api/v3/changes`).
+
+use reqwest::Method;
+use serde::{Deserialize, Serialize, Serializer, ser::SerializeSeq};
+use serde_json::Value;
+
+use crate::{
+    ServiceDesk, TicketID,
+    client::{
+        Priority, Status, TimeEntry, UserInfo, deserialize_optional_name_object,
+        serialize_optional_name_object,
+    },
+    error::Error,
+};
+
+/// A change as returned by SDP.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    pub id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: Option<Status>,
+    /// Change type name, e.g. "Standard", "Normal" or "Emergency"
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_name_object",
+        deserialize_with = "deserialize_optional_name_object"
+    )]
+    pub change_type: Option<String>,
+    /// Risk name, e.g. "Low" or "High"
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_name_object",
+        deserialize_with = "deserialize_optional_name_object"
+    )]
+    pub risk: Option<String>,
+    pub priority: Option<Priority>,
+    pub change_owner: Option<UserInfo>,
+    /// Requests linked to this change
+    #[serde(default)]
+    pub associated_requests: Vec<AssociatedRequest>,
+    pub scheduled_start_time: Option<TimeEntry>,
+    pub scheduled_end_time: Option<TimeEntry>,
+    pub created_time: Option<TimeEntry>,
+    pub udf_fields: Option<Value>,
+}
+
+/// Request linked to a change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssociatedRequest {
+    pub id: TicketID,
+    pub subject: Option<String>,
+}
+
+/// Data for creating a change. Unset optional fields are omitted.
+///
+/// `status`, `change_type` and `risk` are sent by name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ChangeData {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_name_object"
+    )]
+    pub status: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_name_object"
+    )]
+    pub change_type: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_name_object"
+    )]
+    pub risk: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    /// Requests to link the change to on creation
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serialize_request_refs"
+    )]
+    pub associated_requests: Vec<TicketID>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub udf_fields: Option<Value>,
+}
+
+/// Serialize ticket ids as SDP reference objects, `[{ "id": "123" }]`.
+fn serialize_request_refs<S>(ids: &[TicketID], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    #[derive(Serialize)]
+    struct IdRef {
+        id: String,
+    }
+
+    let mut seq = serializer.serialize_seq(Some(ids.len()))?;
+    for id in ids {
+        seq.serialize_element(&IdRef { id: id.to_string() })?;
+    }
+    seq.end()
+}
+
+#[derive(Serialize, Debug)]
+struct CreateChangeRequest<'a> {
+    change: &'a ChangeData,
+}
+
+#[derive(Deserialize, Debug)]
+struct ChangeResponse {
+    change: Change,
+}
+
+impl ServiceDesk {
+    /// Get a single change.
+    pub async fn get_change(&self, id: &str) -> Result<Change, Error> {
+        tracing::info!(change_id = %id, "fetching change");
+        let resp: ChangeResponse = self
+            .request_with_path(Method::GET, &format!("/api/v3/changes/{}", id))
+            .await?;
+        Ok(resp.change)
+    }
+
+    /// Create a new change.
+    pub async fn create_change(&self, data: &ChangeData) -> Result<Change, Error> {
+        tracing::info!(title = %data.title, "creating change");
+        let resp: ChangeResponse = self
+            .request_input_data(
+                Method::POST,
+                "/api/v3/changes",
+                &CreateChangeRequest { change: data },
+            )
+            .await?;
+        Ok(resp.change)
+    }
+}
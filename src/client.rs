@@ -1,15 +1,25 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use std::time::{Duration, Instant};
 
-use reqwest::Method;
+use chrono::{DateTime, Utc};
+use futures_util::{StreamExt, TryStreamExt, stream};
+use reqwest::{Method, header::HeaderMap};
 use serde::{Deserializer, Serialize, Serializer, de::DeserializeOwned, ser::SerializeStruct};
 
+/// Maximum number of in-flight requests for batch operations.
+pub const BATCH_CONCURRENCY: usize = 8;
+
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct InnerResponseMessage {
+    #[serde(deserialize_with = "crate::deserialize_status_code")]
     status_code: u32,
     #[serde(rename = "type")]
     type_field: String,
+    #[serde(default)]
     message: SdpErrorMessage,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    field: Option<String>,
 }
 
 /// Generic SDP response status structure
@@ -18,11 +28,23 @@ pub struct InnerResponseMessage {
 /// conversion.
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SdpResponseStatus {
+    #[serde(deserialize_with = "crate::deserialize_status_code")]
     pub status_code: u32,
     pub messages: Option<Vec<InnerResponseMessage>>,
     pub status: String,
 }
 
+impl SdpResponseStatus {
+    /// Most specific error code: the first failed message's, else the top-level one.
+    fn error_code(&self) -> u32 {
+        self.messages
+            .iter()
+            .flatten()
+            .find(|message| message.status_code != 2000)
+            .map_or(self.status_code, |message| message.status_code)
+    }
+}
+
 impl From<SdpResponseStatus> for Error {
     fn from(status: SdpResponseStatus) -> Self {
         // Try to get the most specific error code and message from messages array
@@ -55,6 +77,52 @@ struct UploadedAttachment {
 }
 
 impl ServiceDesk {
+    /// Attach the configured credentials to an outgoing request.
+    pub(crate) fn apply_auth(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        self.auth.apply(builder)
+    }
+
+    /// Send `request`, retrying per [`ServiceDeskOptions::retry`](crate::ServiceDeskOptions::retry).
+    /// See [`RetryPolicy`](crate::RetryPolicy) for which failures are retried per method.
+    ///
+    /// Non-success responses are turned into an [`Error`].
+    pub(crate) async fn execute(
+        &self,
+        request: reqwest::Request,
+    ) -> Result<reqwest::Response, Error> {
+        let mut request = request;
+        let mut retry = 0;
+        loop {
+            let next = match &self.retry {
+                Some(policy) if retry < policy.max_retries => request.try_clone(),
+                _ => None,
+            };
+            let method = request.method().clone();
+            let (status, code, error, headers) = match self.inner.execute(request).await {
+                Ok(response) if response.error_for_status_ref().is_ok() => return Ok(response),
+                Ok(response) => {
+                    let status = response.status().as_u16();
+                    let headers = response.headers().clone();
+                    let (code, error) = error_from_response(response).await;
+                    (Some(status), code, error, headers)
+                }
+                Err(e) => (None, None, e.into(), HeaderMap::new()),
+            };
+            match (next, &self.retry) {
+                (Some(next), Some(policy))
+                    if policy.is_retryable(&method, status, code, &error) =>
+                {
+                    let delay = policy.delay(retry, &headers, self.now());
+                    tracing::warn!(error = %error, retry = retry + 1, ?delay, "retrying sdp request");
+                    tokio::time::sleep(delay).await;
+                    request = next;
+                    retry += 1;
+                }
+                _ => return Err(error),
+            }
+        }
+    }
+
     pub(crate) async fn request_json<T, R>(
         &self,
         method: Method,
@@ -66,20 +134,17 @@ impl ServiceDesk {
         R: DeserializeOwned,
     {
         let url = self.base_url.join(path)?;
-        let request_builder = self.inner.request(method, url).json(body);
+        let request_builder = self.apply_auth(self.inner.request(method, url)).json(body);
 
-        let response = self.inner.execute(request_builder.build()?).await?;
-        if response.error_for_status_ref().is_err() {
-            let error = response.json::<SdpGenericResponse>().await?;
-            tracing::error!(error = ?error, "SDP Error Response");
-            return Err(error.response_status.into());
-        }
+        let response = self.execute(request_builder.build()?).await?;
 
         let parsed = response.json::<R>().await?;
         tracing::debug!("completed sdp request");
         Ok(parsed)
     }
 
+    /// Send `body` as `input_data` in the form body, unless overridden in
+    /// [`ServiceDeskOptions::input_data_mode`](crate::ServiceDeskOptions::input_data_mode).
     pub(crate) async fn request_form<T, R>(
         &self,
         method: Method,
@@ -90,25 +155,12 @@ impl ServiceDesk {
         T: Serialize + ?Sized + std::fmt::Debug,
         R: DeserializeOwned,
     {
-        let url = self.base_url.join(path)?;
-
-        let request_builder = self
-            .inner
-            .request(method, url)
-            .form(&[("input_data", serde_json::to_string(body)?)]);
-
-        let response = self.inner.execute(request_builder.build()?).await?;
-        if response.error_for_status_ref().is_err() {
-            let error = response.json::<SdpGenericResponse>().await?;
-            tracing::error!(error = ?error, "SDP Error Response");
-            return Err(error.response_status.into());
-        }
-
-        let parsed = response.json::<R>().await?;
-        tracing::debug!("completed sdp request");
-        Ok(parsed)
+        self.request_encoded(InputDataMode::Form, method, path, body)
+            .await
     }
 
+    /// Send `body` as `input_data` in the query string, unless overridden in
+    /// [`ServiceDeskOptions::input_data_mode`](crate::ServiceDeskOptions::input_data_mode).
     pub(crate) async fn request_input_data<T, R>(
         &self,
         method: Method,
@@ -119,25 +171,55 @@ impl ServiceDesk {
         T: Serialize + ?Sized + std::fmt::Debug,
         R: DeserializeOwned,
     {
-        let url = self.base_url.join(path)?;
-
-        let request_builder = self
-            .inner
-            .request(method, url)
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .query(&[("input_data", serde_json::to_string(body)?)]);
+        self.request_encoded(InputDataMode::Query, method, path, body)
+            .await
+    }
 
-        let response = self.inner.execute(request_builder.build()?).await?;
-        if response.error_for_status_ref().is_err() {
-            let error = response.json::<SdpGenericResponse>().await?;
-            tracing::error!(error = ?error, "SDP Error Response");
-            return Err(error.response_status.into());
-        }
+    async fn request_encoded<T, R>(
+        &self,
+        mode: InputDataMode,
+        method: Method,
+        path: &str,
+        body: &T,
+    ) -> Result<R, Error>
+    where
+        T: Serialize + ?Sized + std::fmt::Debug,
+        R: DeserializeOwned,
+    {
+        let request = self.build_encoded(mode, method, path, body)?;
+        let response = self.execute(request).await?;
         let result = response.json::<R>().await?;
         tracing::debug!("completed sdp request");
         Ok(result)
     }
 
+    /// Build a request carrying `body` as `input_data`, placed according to the
+    /// configured override or else the operation's preferred `mode`.
+    fn build_encoded<T>(
+        &self,
+        mode: InputDataMode,
+        method: Method,
+        path: &str,
+        body: &T,
+    ) -> Result<reqwest::Request, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let mut url = self.base_url.join(path)?;
+        let encoded = encode_input_data_for(body, &method, path)?;
+        let builder = match self.input_data_mode.unwrap_or(mode) {
+            InputDataMode::Query => {
+                url.set_query(Some(&encoded));
+                self.inner.request(method, url)
+            }
+            InputDataMode::Form => self.inner.request(method, url).body(encoded),
+        };
+        Ok(self
+            .apply_auth(builder)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .build()?)
+    }
+
     async fn request<T, R>(
         &self,
         method: Method,
@@ -153,20 +235,8 @@ impl ServiceDesk {
             .join(path)?
             .join(&path_parameter.to_string())?;
 
-        let request_builder = self.inner.request(method, url);
-        let response = self.inner.execute(request_builder.build()?).await?;
-        if response.error_for_status_ref().is_err() {
-            let error = response.json::<SdpGenericResponse>().await.map_err(|e| {
-                tracing::error!(error = ?e, "Failed to parse SDP error response");
-                Error::from_sdp(
-                    500,
-                    "Failed to parse SDP error response".to_string(),
-                    Some(e.to_string()),
-                )
-            })?;
-            tracing::error!(error = ?error, "SDP Error Response");
-            return Err(error.response_status.into());
-        }
+        let request_builder = self.apply_auth(self.inner.request(method, url));
+        let response = self.execute(request_builder.build()?).await?;
 
         let response = response.json::<R>().await.map_err(|e| {
             tracing::error!(error = ?e, "Failed to parse SDP response");
@@ -181,26 +251,14 @@ impl ServiceDesk {
         Ok(response)
     }
 
-    async fn request_with_path<R>(&self, method: Method, path: &str) -> Result<R, Error>
+    pub(crate) async fn request_with_path<R>(&self, method: Method, path: &str) -> Result<R, Error>
     where
         R: DeserializeOwned,
     {
         let url = self.base_url.join(path)?;
 
-        let request_builder = self.inner.request(method, url);
-        let response = self.inner.execute(request_builder.build()?).await?;
-        if response.error_for_status_ref().is_err() {
-            let error = response.json::<SdpGenericResponse>().await.map_err(|e| {
-                tracing::error!(error = ?e, "Failed to parse SDP error response");
-                Error::from_sdp(
-                    500,
-                    "Failed to parse SDP error response".to_string(),
-                    Some(e.to_string()),
-                )
-            })?;
-            tracing::error!(error = ?error, "SDP Error Response");
-            return Err(error.response_status.into());
-        }
+        let request_builder = self.apply_auth(self.inner.request(method, url));
+        let response = self.execute(request_builder.build()?).await?;
 
         let parsed = response.json::<R>().await?;
         tracing::debug!("completed sdp request");
@@ -221,7 +279,11 @@ impl ServiceDesk {
             .await?;
 
         tracing::debug!(%upload_url, "uploading attachment");
-        let response = self.inner.post(upload_url).multipart(form).send().await?;
+        let response = self
+            .apply_auth(self.inner.post(upload_url))
+            .multipart(form)
+            .send()
+            .await?;
         let status = response.status();
         let body = response.text().await?;
 
@@ -250,6 +312,13 @@ impl ServiceDesk {
         Ok(())
     }
 
+    /// Fetch a single ticket.
+    ///
+    /// A ticket in trash is reported as [`Error::NotFound`]. Most SDP builds
+    /// answer with status 4007 on their own; builds that still return the
+    /// request with `is_trashed` set are mapped to the same error, so callers
+    /// need a single check. Use [`list_trashed_requests`](Self::list_trashed_requests)
+    /// to inspect trashed tickets.
     pub async fn ticket_details(
         &self,
         ticket_id: impl Into<TicketID>,
@@ -259,9 +328,62 @@ impl ServiceDesk {
         let resp: DetailedTicketResponse = self
             .request(Method::GET, "/api/v3/requests/", &ticket_id)
             .await?;
+        if resp.request.is_deleted {
+            tracing::debug!(ticket_id = %ticket_id, "ticket is in trash");
+            return Err(Error::NotFound(format!("request {ticket_id} is in trash")));
+        }
         Ok(resp.request)
     }
 
+    /// List the secondary accounts (business units) a ticket is linked to,
+    /// in addition to its primary [`DetailedTicket::account`].
+    pub async fn linked_to_accounts(
+        &self,
+        ticket_id: impl Into<TicketID>,
+    ) -> Result<Vec<Account>, Error> {
+        let ticket_id = ticket_id.into();
+        tracing::info!(ticket_id = %ticket_id, "listing linked accounts");
+        let resp: LinkedAccountsResponse = self
+            .request_with_path(
+                Method::GET,
+                &format!("/api/v3/requests/{}/linked_to_accounts", ticket_id),
+            )
+            .await?;
+        Ok(resp.accounts)
+    }
+
+    /// Fetch only the current status of a ticket.
+    pub async fn ticket_status(&self, ticket_id: impl Into<TicketID>) -> Result<Status, Error> {
+        Ok(self.ticket_details(ticket_id).await?.status)
+    }
+
+    /// Poll a ticket until its status name matches `target` (case-insensitive).
+    ///
+    /// Returns the reached [`Status`], or `Error::Other("timed out")` if the
+    /// ticket has not reached the target status once `timeout` elapses.
+    pub async fn wait_for_status(
+        &self,
+        ticket_id: impl Into<TicketID>,
+        target: &str,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<Status, Error> {
+        let ticket_id = ticket_id.into();
+        tracing::info!(ticket_id = %ticket_id, target = %target, "waiting for ticket status");
+        let deadline = Instant::now() + timeout;
+        loop {
+            let status = self.ticket_status(ticket_id).await?;
+            if status.name.eq_ignore_ascii_case(target) {
+                return Ok(status);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::Other("timed out".to_string()));
+            }
+            tokio::time::sleep(poll_interval.min(deadline - now)).await;
+        }
+    }
+
     pub async fn get_conversations(&self, ticket_id: impl Into<TicketID>) -> Result<Value, Error> {
         let ticket_id = ticket_id.into();
         tracing::info!(ticket_id = %ticket_id, "fetching ticket details");
@@ -280,6 +402,41 @@ impl ServiceDesk {
         self.request_with_path(Method::GET, &path).await
     }
 
+    /// Summaries of a ticket's conversations, with attachment counts but no content.
+    pub async fn conversation_summaries(
+        &self,
+        ticket_id: impl Into<TicketID>,
+    ) -> Result<Vec<ConversationSummary>, Error> {
+        Ok(self.get_conversations_typed(ticket_id).await?.conversations)
+    }
+
+    /// List the conversations (emails and notifications) of a ticket.
+    pub async fn list_conversations(
+        &self,
+        ticket_id: impl Into<TicketID>,
+    ) -> Result<Vec<Conversation>, Error> {
+        let ticket_id = ticket_id.into();
+        tracing::info!(ticket_id = %ticket_id, "listing ticket conversations");
+        let path = format!("/api/v3/requests/{}/conversations", &ticket_id);
+        let resp: ConversationListResponse = self.request_with_path(Method::GET, &path).await?;
+        Ok(resp.conversations)
+    }
+
+    /// Notes and conversations of a ticket merged into a single transcript, oldest first.
+    ///
+    /// Items without a timestamp are placed at the end in their original order.
+    /// Every page of notes is included.
+    pub async fn thread(&self, ticket_id: impl Into<TicketID>) -> Result<Vec<ThreadItem>, Error> {
+        let ticket_id = ticket_id.into();
+        let notes: Vec<Note> = self
+            .ticket(ticket_id)
+            .notes_pager(100)
+            .try_collect()
+            .await?;
+        let conversations = self.list_conversations(ticket_id).await?;
+        Ok(merge_thread(notes, conversations))
+    }
+
     pub async fn get_conversation_content(&self, content_url: &str) -> Result<Value, Error> {
         tracing::info!(content_url = %content_url, "fetching conversation content");
         let resp: Value = self.request_with_path(Method::GET, content_url).await?;
@@ -292,14 +449,7 @@ impl ServiceDesk {
     ) -> Result<Vec<Attachment>, Error> {
         tracing::info!(content_url = %content_url, "fetching conversation attachments");
         let resp: Value = self.request_with_path(Method::GET, content_url).await?;
-        let attachment: Vec<Attachment> = serde_json::from_value(
-            resp.get("notification")
-                .unwrap_or(&Value::Null)
-                .get("attachments")
-                .cloned()
-                .unwrap_or_default(),
-        )?;
-        Ok(attachment)
+        conversation_attachments(&resp)
     }
 
     /// List ticket IDs that were merged into the given parent ticket.
@@ -362,48 +512,347 @@ impl ServiceDesk {
         Ok(links)
     }
 
-    pub async fn download_attachment(&self, attachment_url: &str) -> Result<Vec<u8>, Error> {
-        tracing::info!(attachment_url = %attachment_url, "downloading attachment");
-        let url = self.base_url.join(attachment_url)?;
-        let response = self.inner.get(url).send().await?;
-        if response.error_for_status_ref().is_err() {
-            let error = response.json::<SdpGenericResponse>().await.map_err(|e| {
-                tracing::error!(error = ?e, "Failed to parse SDP error response");
-                Error::from_sdp(
-                    500,
-                    "Failed to parse SDP error response".to_string(),
-                    Some(e.to_string()),
-                )
-            })?;
-            tracing::error!(error = ?error, "SDP Error Response");
-            return Err(error.response_status.into());
+    /// Get all attachment links for a ticket, including conversation attachments
+    /// including attachments from merged tickets.
+    ///
+    /// Fails if any conversation carrying attachments can't be read.
+    pub async fn all_attachment_links(
+        &self,
+        ticket_id: impl Into<TicketID>,
+    ) -> Result<Vec<String>, Error> {
+        let ticket_id = ticket_id.into();
+        // Only the attachment list is needed, skip the rest of the ticket.
+        let resp: AttachmentsOnlyResponse = self
+            .request_input_data(
+                Method::GET,
+                &format!("/api/v3/requests/{}", ticket_id),
+                &serde_json::json!({ "fields_required": ATTACHMENT_FIELDS }),
+            )
+            .await?;
+        let mut links = Vec::new();
+        if let Some(attachments) = resp.request.attachments {
+            for attachment in attachments {
+                links.push(normalize_attachment_url(
+                    &self.base_url,
+                    &attachment.content_url,
+                )?);
+            }
         }
+        links.extend(self.get_conversation_attachment_urls(ticket_id).await?);
+        Ok(links)
+    }
+
+    /// Number of distinct attachments on a ticket, counting conversation
+    /// attachments like [`all_attachment_links`](Self::all_attachment_links).
+    ///
+    /// An attachment reachable both from the request and a conversation is
+    /// counted once.
+    pub async fn attachment_count(&self, ticket_id: impl Into<TicketID>) -> Result<usize, Error> {
+        let links = self.all_attachment_links(ticket_id).await?;
+        Ok(links.into_iter().collect::<HashSet<_>>().len())
+    }
+
+    /// Collect attachment links for many tickets concurrently.
+    ///
+    /// At most [`BATCH_CONCURRENCY`] tickets are processed at once. A failure for
+    /// one ticket doesn't abort the others, it is kept as that ticket's entry.
+    pub async fn attachment_links_for(
+        &self,
+        ticket_ids: &[TicketID],
+    ) -> HashMap<TicketID, Result<Vec<String>, Error>> {
+        tracing::info!(count = ticket_ids.len(), "collecting attachment links");
+        stream::iter(ticket_ids.iter().copied())
+            .map(|id| async move { (id, self.all_attachment_links(id).await) })
+            .buffer_unordered(BATCH_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// Whether the ticket has attachments, without listing them.
+    ///
+    /// Only the request's own `has_attachments` flag is fetched; attachments on
+    /// conversations aren't considered, unlike [`all_attachment_links`](Self::all_attachment_links).
+    pub async fn has_attachments(&self, ticket_id: impl Into<TicketID>) -> Result<bool, Error> {
+        let ticket_id = ticket_id.into();
+        tracing::info!(ticket_id = %ticket_id, "checking for attachments");
+        let resp: HasAttachmentsResponse = self
+            .request_input_data(
+                Method::GET,
+                &format!("/api/v3/requests/{}", ticket_id),
+                &serde_json::json!({ "fields_required": HAS_ATTACHMENTS_FIELDS }),
+            )
+            .await?;
+        Ok(resp.request.has_attachments)
+    }
+
+    pub async fn download_attachment(&self, attachment_url: &str) -> Result<Vec<u8>, Error> {
+        let response = self.attachment_response(attachment_url).await?;
         let bytes = response.bytes().await?;
         Ok(bytes.to_vec())
     }
 
+    /// Download an attachment and decode it as text.
+    ///
+    /// Uses the charset from the `Content-Type` header, UTF-8 when there is none.
+    /// Returns [`Error::InvalidValue`] if the bytes aren't valid in that charset.
+    pub async fn download_attachment_text(&self, attachment_url: &str) -> Result<String, Error> {
+        let response = self.attachment_response(attachment_url).await?;
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let bytes = response.bytes().await?;
+        decode_text(&bytes, content_type.as_deref())
+    }
+
+    async fn attachment_response(&self, attachment_url: &str) -> Result<reqwest::Response, Error> {
+        tracing::info!(attachment_url = %attachment_url, "downloading attachment");
+        let url = self.base_url.join(attachment_url)?;
+        let request = self.apply_auth(self.inner.get(url)).build()?;
+        let response = self.execute(request).await?;
+        Ok(response)
+    }
+
+    /// Fetch the size and content type of an attachment without downloading it.
+    ///
+    /// Issues a HEAD request, headers the server doesn't send are `None`.
+    pub async fn attachment_info(&self, attachment_url: &str) -> Result<AttachmentMeta, Error> {
+        tracing::info!(attachment_url = %attachment_url, "fetching attachment metadata");
+        let url = self.base_url.join(attachment_url)?;
+        let request = self.apply_auth(self.inner.head(url)).build()?;
+        let response = self.execute(request).await?;
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+        };
+        Ok(AttachmentMeta {
+            // `Response::content_length` reports the (empty) body size for HEAD, read the header.
+            content_length: header(reqwest::header::CONTENT_LENGTH)
+                .and_then(|value| value.parse().ok()),
+            content_type: header(reqwest::header::CONTENT_TYPE).map(str::to_string),
+        })
+    }
+
     /// Edit an existing ticket.
     ///
     /// # Important
     /// Read `EditTicketData` documentation for details on how the editing works and how to use it.
+    ///
+    /// SDP may apply the edit but reject single fields, e.g. one UDF; those are
+    /// returned as [`Error::Validation`] even though the rest was saved.
     pub async fn edit(
         &self,
         ticket_id: impl Into<TicketID>,
         data: &EditTicketData,
-    ) -> Result<(), Error> {
+    ) -> Result<OperationResult, Error> {
         let ticket_id = ticket_id.into();
         tracing::info!(ticket_id = %ticket_id, "editing ticket");
-        let _: SdpGenericResponse = self
+        check_subject_length(&data.subject, self.max_subject_length)?;
+        let resp: OperationResponse = self
             .request_input_data(
                 Method::PUT,
                 &format!("/api/v3/requests/{}", ticket_id),
                 &EditTicketRequest { request: data },
             )
             .await?;
-        Ok(())
+        let result = resp.result();
+        field_failures(resp.response_status)?;
+        Ok(result)
+    }
+
+    /// Update only the given fields of a ticket.
+    ///
+    /// Unlike [`edit`](Self::edit), keys absent from `fields` are not sent and so
+    /// keep their current value. `fields` is the content of the `request` object,
+    /// e.g. `json!({ "priority": { "name": "High" } })`.
+    pub async fn patch(&self, ticket_id: impl Into<TicketID>, fields: Value) -> Result<(), Error> {
+        let ticket_id = ticket_id.into();
+        tracing::info!(ticket_id = %ticket_id, "patching ticket");
+        let resp: SdpGenericResponse = self
+            .request_input_data(
+                Method::PUT,
+                &format!("/api/v3/requests/{}", ticket_id),
+                &serde_json::json!({ "request": fields }),
+            )
+            .await?;
+        field_failures(resp.response_status)
+    }
+
+    /// Change the request type, e.g. convert an Incident into a "Service Request".
+    ///
+    /// SDP answers 4001 when the new type doesn't fit the ticket's template, that
+    /// is returned as [`Error::InvalidValue`] naming the rejected type.
+    pub async fn reclassify(
+        &self,
+        ticket_id: impl Into<TicketID>,
+        request_type: &str,
+    ) -> Result<(), Error> {
+        let ticket_id = ticket_id.into();
+        tracing::info!(ticket_id = %ticket_id, request_type, "reclassifying ticket");
+        self.patch(
+            ticket_id,
+            serde_json::json!({ "request_type": { "name": request_type } }),
+        )
+        .await
+        .map_err(|e| match e {
+            Error::InvalidValue(detail) => Error::InvalidValue(format!(
+                "request type {request_type:?} rejected, check it matches the ticket's template: {detail}"
+            )),
+            e => e,
+        })
+    }
+
+    /// Move a closed or resolved ticket back to Open.
+    pub async fn reopen_ticket(&self, ticket_id: impl Into<TicketID>) -> Result<(), Error> {
+        let ticket_id = ticket_id.into();
+        tracing::info!(ticket_id = %ticket_id, "reopening ticket");
+        self.patch(
+            ticket_id,
+            serde_json::json!({ "status": { "name": Status::open().name } }),
+        )
+        .await
+    }
+
+    /// Move a ticket back to Open, asking SDP to skip the closure rules that
+    /// would otherwise block it.
+    ///
+    /// Unlike [`reopen_ticket`](Self::reopen_ticket), which is refused with
+    /// [`Error::ClosureRuleViolation`] (4003) when a rule forbids reopening, this
+    /// sends the `force_reopen` flag. Instances that don't know the flag reject
+    /// it as an unknown field; that, like a 4003, is returned as
+    /// [`Error::ClosureRuleViolation`] so callers can handle both the same way.
+    pub async fn reopen_forced(&self, ticket_id: impl Into<TicketID>) -> Result<(), Error> {
+        let ticket_id = ticket_id.into();
+        tracing::info!(ticket_id = %ticket_id, "force reopening ticket");
+        self.patch(
+            ticket_id,
+            serde_json::json!({
+                "status": { "name": Status::open().name },
+                "force_reopen": true,
+            }),
+        )
+        .await
+        .map_err(|e| match e {
+            Error::NoSuchField(_) => Error::ClosureRuleViolation(
+                "forced reopen is not supported on this instance".into(),
+            ),
+            Error::ClosureRuleViolation(detail) => {
+                Error::ClosureRuleViolation(format!("forced reopen refused: {detail}"))
+            }
+            e => e,
+        })
+    }
+
+    /// Move a request to another site and/or group through SDP's move operation.
+    ///
+    /// Unlike an edit this runs the move workflow, which remaps fields for the
+    /// destination. Fields the destination makes mandatory but the request
+    /// lacks are reported as [`Error::MissingField`], naming the destination.
+    /// Returns [`Error::InvalidValue`] when neither `site` nor `group` is given.
+    pub async fn move_request(
+        &self,
+        ticket_id: impl Into<TicketID>,
+        site: Option<&str>,
+        group: Option<&str>,
+    ) -> Result<(), Error> {
+        let ticket_id = ticket_id.into();
+        if site.is_none() && group.is_none() {
+            return Err(Error::InvalidValue(
+                "moving a request needs a site or a group".into(),
+            ));
+        }
+        tracing::info!(ticket_id = %ticket_id, ?site, ?group, "moving ticket");
+        let mut request = serde_json::Map::new();
+        if let Some(site) = site {
+            request.insert("site".into(), serde_json::json!({ "name": site }));
+        }
+        if let Some(group) = group {
+            request.insert("group".into(), serde_json::json!({ "name": group }));
+        }
+        let resp: SdpGenericResponse = self
+            .request_input_data(
+                Method::PUT,
+                &format!("/api/v3/requests/{}/_move", ticket_id),
+                &serde_json::json!({ "request": request }),
+            )
+            .await
+            .map_err(|e| match e {
+                Error::MissingField(field) => {
+                    let destination = [
+                        site.map(|s| format!("site {s:?}")),
+                        group.map(|g| format!("group {g:?}")),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                    Error::MissingField(format!("{field} (mandatory in {destination})"))
+                }
+                e => e,
+            })?;
+        field_failures(resp.response_status)
+    }
+
+    /// Raise a ticket's priority one level, see [`Priority::escalated`], and
+    /// return the new priority.
+    ///
+    /// A ticket without a priority becomes Low. At Critical nothing is sent.
+    /// Returns [`Error::InvalidValue`] for a custom priority outside the
+    /// standard levels.
+    pub async fn escalate_priority(
+        &self,
+        ticket_id: impl Into<TicketID>,
+    ) -> Result<Priority, Error> {
+        let ticket_id = ticket_id.into();
+        let current = self.ticket_details(ticket_id).await?.priority;
+        let next = match &current {
+            None => Priority::low(),
+            Some(priority) => priority.escalated().ok_or_else(|| {
+                Error::InvalidValue(format!(
+                    "can't escalate non-standard priority {:?}",
+                    priority.name
+                ))
+            })?,
+        };
+        if current
+            .as_ref()
+            .is_some_and(|priority| priority.id == next.id)
+        {
+            return Ok(next);
+        }
+        tracing::info!(ticket_id = %ticket_id, priority = %next.name, "escalating priority");
+        self.patch(
+            ticket_id,
+            serde_json::json!({ "priority": { "id": next.id } }),
+        )
+        .await?;
+        Ok(next)
+    }
+
+    /// Change only the priority of a ticket, by priority name.
+    ///
+    /// Returns [`Error::NotEditable`] when SDP refuses the change, e.g. when the
+    /// priority is controlled by an SLA or business rule.
+    pub async fn set_priority(
+        &self,
+        ticket_id: impl Into<TicketID>,
+        priority: &str,
+    ) -> Result<(), Error> {
+        self.patch(
+            ticket_id,
+            serde_json::json!({ "priority": { "name": priority } }),
+        )
+        .await
     }
 
     /// Add a note to a ticket (creates a new note).
+    ///
+    /// The description is sent verbatim and SDP renders it as HTML, so newlines
+    /// in plain text are lost. [`NoteBuilder::plain_text`](crate::NoteBuilder::plain_text)
+    /// converts plain text first.
     pub async fn add_note(
         &self,
         ticket_id: impl Into<TicketID>,
@@ -421,6 +870,80 @@ impl ServiceDesk {
         Ok(resp.note)
     }
 
+    /// Send a reply e-mail from a ticket to the given recipients.
+    ///
+    /// Fields SDP rejected while still sending the reply are returned as
+    /// [`Error::Validation`].
+    pub async fn reply(
+        &self,
+        ticket_id: impl Into<TicketID>,
+        reply: &ReplyData,
+    ) -> Result<(), Error> {
+        let ticket_id = ticket_id.into();
+        tracing::info!(ticket_id = %ticket_id, recipients = reply.to.len(), "replying to ticket");
+        let resp: SdpGenericResponse = self
+            .request_input_data(
+                Method::POST,
+                &format!("/api/v3/requests/{}/notifications", ticket_id),
+                &ReplyRequest::new(reply),
+            )
+            .await?;
+        field_failures(resp.response_status)
+    }
+
+    /// Add a note unless a recent note already carries `key`.
+    ///
+    /// The key is appended to the description as `[idempotency-key: <key>]`.
+    /// Before posting, the latest notes of the ticket are searched for that marker
+    /// and, if found, the existing note is returned instead of creating a new one.
+    ///
+    /// This is a best-effort client-side check, not a server-side guarantee: two
+    /// calls racing each other can both miss the marker and create duplicates, and
+    /// only the most recent 100 notes are searched.
+    pub async fn add_note_idempotent(
+        &self,
+        ticket_id: impl Into<TicketID>,
+        note: &NoteData,
+        key: &str,
+    ) -> Result<Note, Error> {
+        let ticket_id = ticket_id.into();
+        let marker = format!("[idempotency-key: {key}]");
+        let existing = self.recent_notes(ticket_id, 100).await?;
+        if let Some(found) = existing
+            .into_iter()
+            .find(|n| n.description.contains(&marker))
+        {
+            tracing::info!(ticket_id = %ticket_id, note_id = %found.id, "note already exists, skipping");
+            return Ok(found);
+        }
+
+        let note = NoteData {
+            description: format!("{}<br>{}", note.description, marker),
+            ..note.clone()
+        };
+        self.add_note(ticket_id, &note).await
+    }
+
+    /// List the most recently created notes of a ticket, newest first.
+    async fn recent_notes(&self, ticket_id: TicketID, count: u32) -> Result<Vec<Note>, Error> {
+        let body = ListNotesRequest {
+            list_info: NotesListInfo {
+                row_count: count,
+                start_index: 1,
+                sort_field: Some("created_time".to_string()),
+                sort_order: Some("desc".to_string()),
+            },
+        };
+        let resp: NotesListResponse = self
+            .request_input_data(
+                Method::GET,
+                &format!("/api/v3/requests/{}/notes", ticket_id),
+                &body,
+            )
+            .await?;
+        Ok(resp.notes)
+    }
+
     pub async fn add_worklog(
         &self,
         ticket_id: impl Into<TicketID>,
@@ -459,12 +982,30 @@ impl ServiceDesk {
         row_count: Option<u32>,
         start_index: Option<u32>,
     ) -> Result<Vec<Note>, Error> {
-        let ticket_id = ticket_id.into();
-        tracing::info!(ticket_id = %ticket_id, "listing notes");
+        let resp = self
+            .list_notes_page(
+                ticket_id.into(),
+                row_count.unwrap_or(100),
+                start_index.unwrap_or(1),
+            )
+            .await?;
+        Ok(resp.notes)
+    }
+
+    /// Fetch one page of notes together with its paging info.
+    pub(crate) async fn list_notes_page(
+        &self,
+        ticket_id: TicketID,
+        row_count: u32,
+        start_index: u32,
+    ) -> Result<NotesListResponse, Error> {
+        tracing::info!(ticket_id = %ticket_id, start_index, "listing notes");
         let body = ListNotesRequest {
             list_info: NotesListInfo {
-                row_count: row_count.unwrap_or(100),
-                start_index: start_index.unwrap_or(1),
+                row_count,
+                start_index,
+                sort_field: None,
+                sort_order: None,
             },
         };
         let resp: Value = self
@@ -474,8 +1015,15 @@ impl ServiceDesk {
                 &body,
             )
             .await?;
-        let resp: NotesListResponse = serde_json::from_value(resp)?;
-        Ok(resp.notes)
+        // SDP can report a failure here with HTTP 200 and an empty notes list.
+        let statuses: Vec<SdpResponseStatus> =
+            serde_json::from_value(resp["response_status"].clone())?;
+        if let Some(failed) = statuses.into_iter().find(|s| s.status_code != 2000) {
+            tracing::error!(ticket_id = %ticket_id, status_code = failed.error_code(), "listing notes failed");
+            return Err(failed.into());
+        }
+        let page: NotesListResponse = serde_json::from_value(resp)?;
+        Ok(page)
     }
 
     /// Edit an existing note.
@@ -522,10 +1070,10 @@ impl ServiceDesk {
         &self,
         ticket_id: impl Into<TicketID>,
         technician_name: &str,
-    ) -> Result<(), Error> {
+    ) -> Result<OperationResult, Error> {
         let ticket_id = ticket_id.into();
         tracing::info!(ticket_id = %ticket_id, technician = %technician_name, "assigning ticket");
-        let _: SdpGenericResponse = self
+        let resp: OperationResponse = self
             .request_input_data(
                 Method::PUT,
                 &format!("/api/v3/requests/{}/assign", ticket_id),
@@ -536,12 +1084,13 @@ impl ServiceDesk {
                 },
             )
             .await?;
-        Ok(())
+        Ok(resp.result())
     }
 
     /// Create a new ticket.
     pub async fn create_ticket(&self, data: &CreateTicketData) -> Result<TicketData, Error> {
         tracing::info!(subject = %data.subject, "creating ticket");
+        check_subject_length(&data.subject, self.max_subject_length)?;
         let resp: TicketResponse = self
             .request_input_data(
                 Method::POST,
@@ -558,15 +1107,28 @@ impl ServiceDesk {
     /// The default method of querying is not straightforward,
     /// [`Criteria`] struct on the 'root' level contains a single condition, to combine multiple conditions
     /// use the 'children' field with appropriate `LogicalOp`.
+    /// Returns at most 100 tickets, use [`search_tickets_with`](Self::search_tickets_with)
+    /// for a different page size.
     pub async fn search_tickets(&self, criteria: Criteria) -> Result<Vec<DetailedTicket>, Error> {
-        tracing::info!("searching tickets");
+        self.search_tickets_with(criteria, 100).await
+    }
+
+    /// Like [`search_tickets`](Self::search_tickets), returning at most `row_count` tickets.
+    pub async fn search_tickets_with(
+        &self,
+        criteria: Criteria,
+        row_count: u32,
+    ) -> Result<Vec<DetailedTicket>, Error> {
+        tracing::info!(row_count, "searching tickets");
         let resp = self
             .request_input_data(
                 Method::GET,
                 "/api/v3/requests",
                 &SearchRequest {
                     list_info: ListInfo {
-                        row_count: 100,
+                        row_count,
+                        start_index: None,
+                        get_total_count: None,
                         search_criteria: criteria,
                     },
                 },
@@ -578,15 +1140,284 @@ impl ServiceDesk {
         Ok(ticket_response.requests)
     }
 
+    /// One page of search results together with the `list_info` SDP echoes back.
+    ///
+    /// Low-level building block for custom paginators: `start_index` is 1-based
+    /// and passed through as is. A response without `list_info` yields its defaults.
+    pub async fn search_raw(
+        &self,
+        criteria: Criteria,
+        row_count: u32,
+        start_index: u32,
+    ) -> Result<(Vec<DetailedTicket>, ListInfoResponse), Error> {
+        tracing::info!(row_count, start_index, "searching tickets");
+        let resp: TicketSearchResponse = self
+            .request_input_data(
+                Method::GET,
+                "/api/v3/requests",
+                &SearchRequest {
+                    list_info: ListInfo {
+                        row_count,
+                        start_index: Some(start_index),
+                        get_total_count: None,
+                        search_criteria: criteria,
+                    },
+                },
+            )
+            .await?;
+        Ok((resp.requests, resp.list_info.unwrap_or_default()))
+    }
+
+    /// Search tickets whose `time_field` lies between `from` and `to`, one query per `window`.
+    ///
+    /// Every page of each window is fetched, see
+    /// [`TicketSearchBuilder::fetch_all`](crate::TicketSearchBuilder::fetch_all);
+    /// short windows keep each query's result set small. Window bounds are
+    /// inclusive on both ends; tickets on a boundary are returned once, see
+    /// [`dedup_tickets`].
+    pub async fn search_windowed(
+        &self,
+        criteria: Criteria,
+        time_field: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        window: Duration,
+    ) -> Result<Vec<DetailedTicket>, Error> {
+        let step = chrono::Duration::from_std(window)
+            .ok()
+            .filter(|step| *step > chrono::Duration::zero())
+            .ok_or_else(|| Error::InvalidValue(format!("invalid search window: {window:?}")))?;
+        tracing::info!(time_field, %from, %to, ?window, "searching tickets in windows");
+
+        let bound = |condition, time: DateTime<Utc>| Criteria {
+            field: time_field.to_string(),
+            condition,
+            value: time.timestamp_millis().to_string().into(),
+            children: vec![],
+            logical_operator: Some(LogicalOp::And),
+        };
+        let mut tickets = Vec::new();
+        let mut start = from;
+        while start <= to {
+            let end = start.checked_add_signed(step).unwrap_or(to).min(to);
+            let mut windowed = criteria.clone();
+            windowed.children.push(bound(
+                Condition::GreaterThan,
+                start - chrono::Duration::milliseconds(1),
+            ));
+            windowed.children.push(bound(
+                Condition::LesserThan,
+                end + chrono::Duration::milliseconds(1),
+            ));
+            tickets.extend(
+                self.tickets()
+                    .search()
+                    .criteria(windowed)
+                    .fetch_all()
+                    .await?,
+            );
+            if end == to {
+                break;
+            }
+            start = end;
+        }
+        Ok(dedup_tickets(tickets))
+    }
+
+    /// Fetch the ticket carrying the human-facing `display_id`, e.g. "SR-1042".
+    ///
+    /// Returns [`Error::NotFound`] if no ticket has that reference and
+    /// [`Error::NotUnique`] if several do.
+    pub async fn get_by_reference(&self, reference: &str) -> Result<DetailedTicket, Error> {
+        tracing::info!(reference, "fetching ticket by reference");
+        let criteria = Criteria {
+            field: "display_id".to_string(),
+            condition: Condition::Is,
+            value: reference.into(),
+            ..Default::default()
+        };
+        let mut matches = self.search_tickets_with(criteria, 2).await?;
+        match matches.len() {
+            0 => Err(Error::NotFound(format!(
+                "no ticket with reference {reference:?}"
+            ))),
+            1 => Ok(matches.remove(0)),
+            _ => Err(Error::NotUnique(format!(
+                "several tickets have reference {reference:?}"
+            ))),
+        }
+    }
+
+    /// Count the tickets matching `criteria` without fetching them.
+    pub async fn count_tickets(&self, criteria: Criteria) -> Result<u64, Error> {
+        tracing::info!("counting tickets");
+        let resp: TicketSearchResponse = self
+            .request_input_data(
+                Method::GET,
+                "/api/v3/requests",
+                &SearchRequest {
+                    list_info: ListInfo {
+                        row_count: 1,
+                        start_index: None,
+                        get_total_count: Some(true),
+                        search_criteria: criteria,
+                    },
+                },
+            )
+            .await?;
+        resp.list_info
+            .and_then(|info| info.total_count)
+            .ok_or_else(|| Error::Other("SDP did not report a total count".into()))
+    }
+
+    /// Count the tickets matching `base_criteria` for every status on the instance.
+    ///
+    /// Issues one count query per status, at most [`BATCH_CONCURRENCY`] at once.
+    /// Statuses without matching tickets are included with a count of 0.
+    pub async fn status_breakdown(
+        &self,
+        base_criteria: Criteria,
+    ) -> Result<HashMap<String, u64>, Error> {
+        let statuses = self.list_statuses().await?;
+        tracing::info!(count = statuses.len(), "counting tickets per status");
+        let base = &base_criteria;
+        stream::iter(statuses)
+            .map(|status| async move {
+                let criteria = Criteria {
+                    field: "status.name".to_string(),
+                    condition: Condition::Is,
+                    value: status.name.clone().into(),
+                    children: vec![Criteria {
+                        logical_operator: Some(LogicalOp::And),
+                        ..base.clone()
+                    }],
+                    logical_operator: None,
+                };
+                Ok((status.name, self.count_tickets(criteria).await?))
+            })
+            .buffer_unordered(BATCH_CONCURRENCY)
+            .collect::<Vec<Result<_, Error>>>()
+            .await
+            .into_iter()
+            .collect()
+    }
+
+    /// Fetch the user that owns the auth token.
+    pub async fn current_user(&self) -> Result<UserInfo, Error> {
+        tracing::info!("fetching current user");
+        let resp: CurrentUserResponse = self
+            .request_with_path(Method::GET, "/api/v3/users/me")
+            .await?;
+        Ok(resp.user)
+    }
+
+    /// Fetch the instance's build number and API version.
+    ///
+    /// Useful to work around quirks of specific on-prem builds, e.g. with
+    /// [`ServiceDeskOptions::input_data_mode`](crate::ServiceDeskOptions::input_data_mode).
+    pub async fn server_info(&self) -> Result<ServerInfo, Error> {
+        tracing::info!("fetching server info");
+        let resp: ServerInfoResponse = self
+            .request_with_path(Method::GET, "/api/v3/app_resources/build_info")
+            .await?;
+        Ok(resp.build_info)
+    }
+
+    /// Search users whose name or email address contains `query`.
+    pub async fn search_users(&self, query: &str) -> Result<Vec<UserInfo>, Error> {
+        tracing::info!(query, "searching users");
+        let contains = |field: &str| Criteria {
+            field: field.to_string(),
+            condition: Condition::Contains,
+            value: query.into(),
+            ..Default::default()
+        };
+        let resp: UsersListResponse = self
+            .request_input_data(
+                Method::GET,
+                "/api/v3/users",
+                &SearchRequest {
+                    list_info: ListInfo {
+                        row_count: 100,
+                        start_index: None,
+                        get_total_count: None,
+                        search_criteria: Criteria {
+                            children: vec![Criteria {
+                                logical_operator: Some(LogicalOp::Or),
+                                ..contains("email_id")
+                            }],
+                            ..contains("name")
+                        },
+                    },
+                },
+            )
+            .await?;
+        Ok(resp.users)
+    }
+
+    /// Resolve a requester name or email address to the user record SDP knows.
+    ///
+    /// A case-insensitive exact match on name or email wins over partial
+    /// matches. Otherwise the search must return exactly one user.
+    /// Returns [`Error::NotUnique`] when several users match and
+    /// [`Error::NotFound`] when none do.
+    pub async fn resolve_requester(&self, name_or_email: &str) -> Result<UserInfo, Error> {
+        let candidates = self.search_users(name_or_email).await?;
+        pick_unique_user(name_or_email, candidates)
+    }
+
+    /// Search tickets assigned to the user that owns the auth token.
+    ///
+    /// Costs one extra request to resolve the current user first. Every page of
+    /// results is fetched.
+    pub async fn my_tickets(&self) -> Result<Vec<DetailedTicket>, Error> {
+        let user = self.current_user().await?;
+        tracing::info!(user_id = %user.id, "searching tickets assigned to current user");
+        self.tickets()
+            .search()
+            .field_equals("technician.id", user.id.0)
+            .fetch_all()
+            .await
+    }
+
+    /// Fetch every request raised by a requester, for self-service views.
+    ///
+    /// `name_or_email` is matched as described in
+    /// [`TicketSearchBuilder::requester_is`](crate::TicketSearchBuilder::requester_is).
+    pub async fn requests_for_requester(
+        &self,
+        name_or_email: &str,
+    ) -> Result<Vec<DetailedTicket>, Error> {
+        tracing::info!(requester = name_or_email, "searching tickets by requester");
+        self.tickets()
+            .search()
+            .requester_is(name_or_email)
+            .fetch_all()
+            .await
+    }
+
     /// Close a ticket with closure comments.
     pub async fn close_ticket(
         &self,
         ticket_id: impl Into<TicketID>,
         closure_comments: &str,
-    ) -> Result<(), Error> {
+    ) -> Result<OperationResult, Error> {
+        self.close_with_code(ticket_id, closure_comments, "Closed")
+            .await
+    }
+
+    /// Close a ticket with closure comments and a specific closure code.
+    ///
+    /// The code must exist on the instance, see [`list_closure_codes`](Self::list_closure_codes).
+    pub async fn close_with_code(
+        &self,
+        ticket_id: impl Into<TicketID>,
+        closure_comments: &str,
+        closure_code: &str,
+    ) -> Result<OperationResult, Error> {
         let ticket_id = ticket_id.into();
-        tracing::info!(ticket_id = %ticket_id, "closing ticket");
-        let _: SdpGenericResponse = self
+        tracing::info!(ticket_id = %ticket_id, closure_code, "closing ticket");
+        let resp: OperationResponse = self
             .request_json(
                 Method::PUT,
                 &format!("/api/v3/requests/{}/close", ticket_id),
@@ -594,33 +1425,230 @@ impl ServiceDesk {
                     request: CloseTicketData {
                         closure_info: ClosureInfo {
                             closure_comments: closure_comments.to_string(),
-                            closure_code: "Closed".to_string(),
+                            closure_code: closure_code.to_string(),
+                        },
+                    },
+                },
+            )
+            .await?;
+        Ok(resp.result())
+    }
+
+    /// Set the resolution text of a ticket, replacing any existing one.
+    pub async fn add_resolution(
+        &self,
+        ticket_id: impl Into<TicketID>,
+        content: &str,
+    ) -> Result<(), Error> {
+        let ticket_id = ticket_id.into();
+        tracing::info!(ticket_id = %ticket_id, "adding resolution");
+        let _: SdpGenericResponse = self
+            .request_input_data(
+                Method::PUT,
+                &format!("/api/v3/requests/{}/resolutions", ticket_id),
+                &serde_json::json!({ "resolution": { "content": content } }),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Close many tickets concurrently with the same comments and closure code.
+    ///
+    /// At most [`BATCH_CONCURRENCY`] tickets are closed at once. Each result lines
+    /// up with the ticket at the same position in `ticket_ids`; a failure for one
+    /// ticket doesn't abort the others.
+    pub async fn bulk_close(
+        &self,
+        ticket_ids: &[TicketID],
+        closure_comments: &str,
+        closure_code: &str,
+    ) -> Vec<Result<OperationResult, Error>> {
+        tracing::info!(count = ticket_ids.len(), closure_code, "closing tickets");
+        stream::iter(ticket_ids.iter().copied())
+            .map(|id| self.close_with_code(id, closure_comments, closure_code))
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// Assign many tickets concurrently to the same technician, e.g. to hand a
+    /// batch of new alerts to whoever is on call.
+    ///
+    /// At most [`BATCH_CONCURRENCY`] tickets are assigned at once. Each result
+    /// lines up with the ticket at the same position in `ticket_ids`.
+    pub async fn bulk_assign(
+        &self,
+        ticket_ids: &[TicketID],
+        technician_name: &str,
+    ) -> Vec<Result<OperationResult, Error>> {
+        tracing::info!(count = ticket_ids.len(), technician = %technician_name, "assigning tickets");
+        stream::iter(ticket_ids.iter().copied())
+            .map(|id| self.assign_ticket(id, technician_name))
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// Link a request to the problem behind it.
+    ///
+    /// An existing link is reported as [`Association::AlreadyLinked`] instead of
+    /// [`Error::ReferenceExists`].
+    pub async fn associate_problem(
+        &self,
+        ticket_id: impl Into<TicketID>,
+        problem_id: &str,
+    ) -> Result<Association, Error> {
+        self.associate(ticket_id.into(), "problem", problem_id)
+            .await
+    }
+
+    /// Link a request to a change.
+    ///
+    /// An existing link is reported as [`Association::AlreadyLinked`] instead of
+    /// [`Error::ReferenceExists`].
+    pub async fn associate_change(
+        &self,
+        ticket_id: impl Into<TicketID>,
+        change_id: &str,
+    ) -> Result<Association, Error> {
+        self.associate(ticket_id.into(), "change", change_id).await
+    }
+
+    async fn associate(
+        &self,
+        ticket_id: TicketID,
+        kind: &str,
+        id: &str,
+    ) -> Result<Association, Error> {
+        tracing::info!(ticket_id = %ticket_id, kind, id, "associating request");
+        let result: Result<AssociationResponse, Error> = self
+            .request_input_data(
+                Method::PUT,
+                &format!("/api/v3/requests/{}/{}", ticket_id, kind),
+                &serde_json::json!({ kind: { "id": id } }),
+            )
+            .await;
+        match result {
+            Ok(resp) => Ok(Association::Linked(resp.associated)),
+            Err(Error::ReferenceExists) => {
+                tracing::debug!(ticket_id = %ticket_id, kind, id, "association already exists");
+                Ok(Association::AlreadyLinked)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Get the SLA applied to a ticket, with its due times and escalation levels.
+    ///
+    /// Only available on SDP versions that expose `/api/v3/requests/{id}/sla`.
+    pub async fn get_sla(&self, ticket_id: impl Into<TicketID>) -> Result<Sla, Error> {
+        let ticket_id = ticket_id.into();
+        tracing::info!(ticket_id = %ticket_id, "fetching ticket sla");
+        let resp: SlaResponse = self
+            .request_with_path(Method::GET, &format!("/api/v3/requests/{}/sla", ticket_id))
+            .await?;
+        Ok(resp.sla)
+    }
+
+    /// List the closure codes configured on the instance.
+    pub async fn list_closure_codes(&self) -> Result<Vec<ClosureCode>, Error> {
+        tracing::info!("listing closure codes");
+        let resp: ClosureCodesResponse = self
+            .request_with_path(Method::GET, "/api/v3/closure_codes")
+            .await?;
+        Ok(resp.closure_codes)
+    }
+
+    /// Fetch a request template by name, including its default field values.
+    ///
+    /// The template is looked up by name, then its details are fetched; their
+    /// `request` object becomes [`Template::defaults`]. Returns
+    /// [`Error::NotFound`] when no template has that name.
+    pub async fn get_template(&self, name: &str) -> Result<Template, Error> {
+        tracing::info!(name, "fetching request template");
+        let list: TemplatesListResponse = self
+            .request_input_data(
+                Method::GET,
+                "/api/v3/request_templates",
+                &SearchRequest {
+                    list_info: ListInfo {
+                        row_count: 1,
+                        start_index: None,
+                        get_total_count: None,
+                        search_criteria: Criteria {
+                            field: "name".to_string(),
+                            condition: Condition::Is,
+                            value: name.into(),
+                            children: vec![],
+                            logical_operator: None,
                         },
                     },
                 },
             )
             .await?;
+        let info = list
+            .request_templates
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::NotFound(format!("request template {name:?}")))?;
+        let resp: TemplateDetailsResponse = self
+            .request_with_path(
+                Method::GET,
+                &format!("/api/v3/request_templates/{}", info.id),
+            )
+            .await?;
+        Ok(resp.request_template)
+    }
+
+    /// List the request statuses configured on the instance.
+    pub async fn list_statuses(&self) -> Result<Vec<Status>, Error> {
+        tracing::info!("listing statuses");
+        let resp: StatusesResponse = self
+            .request_with_path(Method::GET, "/api/v3/statuses")
+            .await?;
+        Ok(resp.statuses)
+    }
+
+    /// List requests currently in the trash.
+    pub async fn list_trashed_requests(&self) -> Result<Vec<TrashedTicket>, Error> {
+        tracing::info!("listing trashed requests");
+        let resp: TrashListResponse = self
+            .request_with_path(Method::GET, "/api/v3/requests/trash")
+            .await?;
+        Ok(resp.requests)
+    }
+
+    /// Permanently delete the given requests from the trash.
+    ///
+    /// Only reachable through [`TicketsClient::empty_trash`](crate::TicketsClient::empty_trash),
+    /// which requires confirming the number of tickets first.
+    pub(crate) async fn empty_trash(&self, ticket_ids: &[TicketID]) -> Result<(), Error> {
+        tracing::info!(count = ticket_ids.len(), "emptying trash");
+        let ids: Vec<String> = ticket_ids.iter().map(|id| id.to_string()).collect();
+        let _: SdpGenericResponse = self
+            .request_input_data(
+                Method::DELETE,
+                "/api/v3/requests/trash",
+                &serde_json::json!({ "ids": ids }),
+            )
+            .await?;
         Ok(())
     }
 
     /// Merge multiple tickets into a single ticket.
     /// Key point to note is that the maximum number of tickets that can be merged at once is 49 +
     /// 1 (the target ticket), so the `merge_ids` slice must not exceed 49 IDs.
+    ///
+    /// SDP may merge only some of the tickets, the returned [`MergeResult`] lists
+    /// which ones were merged and why the others failed.
     pub async fn merge(
         &self,
         ticket_id: impl Into<TicketID>,
         merge_ids: &[TicketID],
-    ) -> Result<(), Error> {
+    ) -> Result<MergeResult, Error> {
         let ticket_id = ticket_id.into();
         tracing::info!(ticket_id = %ticket_id, count = merge_ids.len(), "merging tickets");
-        if merge_ids.len() > 49 {
-            tracing::warn!("attempted to merge more than 49 tickets");
-            return Err(Error::from_sdp(
-                400,
-                "Cannot merge more than 49 tickets at once".to_string(),
-                None,
-            ));
-        }
+        check_merge_count(merge_ids)?;
         let merge_requests: Vec<MergeRequestId> = merge_ids
             .iter()
             .map(|id| MergeRequestId {
@@ -628,15 +1656,190 @@ impl ServiceDesk {
             })
             .collect();
 
-        let _: SdpGenericResponse = self
+        let resp: MergeResponse = self
             .request_form(
                 Method::PUT,
                 &format!("/api/v3/requests/{}/merge_requests", ticket_id),
                 &MergeTicketsRequest { merge_requests },
             )
             .await?;
+        resp.into_result(merge_ids)
+    }
+
+    /// Merge tickets into `ticket_id`, copying each source ticket's notes first.
+    ///
+    /// Every note of every source, across all pages, is re-added to the target with a prefix naming
+    /// the source ticket, then the regular [`merge`](Self::merge) is performed.
+    /// This is not transactional: if a later step fails, notes copied so far stay
+    /// on the target.
+    pub async fn merge_preserving(
+        &self,
+        ticket_id: impl Into<TicketID>,
+        sources: &[TicketID],
+    ) -> Result<MergeResult, Error> {
+        let ticket_id = ticket_id.into();
+        tracing::info!(ticket_id = %ticket_id, count = sources.len(), "merging tickets preserving notes");
+        check_merge_count(sources)?;
+        for source in sources {
+            let notes: Vec<Note> = self.ticket(*source).notes_pager(100).try_collect().await?;
+            for note in notes {
+                let copy = NoteData {
+                    description: format!(
+                        "[Copied from request {}]<br>{}",
+                        source, note.description
+                    ),
+                    ..Default::default()
+                };
+                self.add_note(ticket_id, &copy).await?;
+            }
+        }
+        self.merge(ticket_id, sources).await
+    }
+}
+
+/// Encode a body the way SDP expects it: `input_data=<url-encoded JSON>`.
+///
+/// This is exactly the query string sent by query-style requests and the form
+/// body sent by form-style ones, handy for replaying a payload in Postman or curl.
+pub fn encode_input_data<T: Serialize + ?Sized>(body: &T) -> Result<String, Error> {
+    serde_urlencoded::to_string([("input_data", serde_json::to_string(body)?)]).map_err(|source| {
+        Error::FormEncoding {
+            context: "encoding input_data".to_string(),
+            source,
+        }
+    })
+}
+
+/// Like [`encode_input_data`], naming the request in a [`Error::FormEncoding`].
+fn encode_input_data_for<T: Serialize + ?Sized>(
+    body: &T,
+    method: &Method,
+    path: &str,
+) -> Result<String, Error> {
+    encode_input_data(body).map_err(|e| with_encoding_context(e, method, path))
+}
+
+fn with_encoding_context(error: Error, method: &Method, path: &str) -> Error {
+    match error {
+        Error::FormEncoding { source, .. } => Error::FormEncoding {
+            context: format!("encoding input_data for {method} {path}"),
+            source,
+        },
+        other => other,
+    }
+}
+
+/// Drop repeated tickets, keeping the first occurrence of every [`TicketID`] in order.
+///
+/// Useful when combining the results of overlapping or windowed searches.
+pub fn dedup_tickets(tickets: Vec<DetailedTicket>) -> Vec<DetailedTicket> {
+    let mut seen = HashSet::new();
+    tickets
+        .into_iter()
+        .filter(|ticket| seen.insert(ticket.id))
+        .collect()
+}
+
+/// Turn field-level failures in an otherwise successful response into [`Error::Validation`].
+fn field_failures(status: SdpResponseStatus) -> Result<(), Error> {
+    let errors: Vec<FieldError> = status
+        .messages
+        .into_iter()
+        .flatten()
+        .filter(|m| m.status_code != 2000)
+        .map(|m| FieldError {
+            field: m.field,
+            code: m.status_code,
+            message: m.message.into_text(),
+        })
+        .collect();
+    if errors.is_empty() {
         Ok(())
+    } else {
+        tracing::warn!(count = errors.len(), "SDP rejected some fields");
+        Err(Error::Validation(errors))
+    }
+}
+
+/// Longest part of an unparseable error body kept in [`Error::UnexpectedResponse`].
+const ERROR_BODY_LIMIT: usize = 1024;
+
+/// Turn a non-success response into an [`Error`].
+///
+/// The body is read through reqwest so gzip'd bodies arrive decoded. Bodies that
+/// aren't an SDP status object (proxy error pages and the like) are kept, shortened,
+/// in [`Error::UnexpectedResponse`]. The SDP error code is returned alongside
+/// when the body carried one.
+async fn error_from_response(response: reqwest::Response) -> (Option<u32>, Error) {
+    let status = response.status().as_u16();
+    let body = match response.text().await {
+        Ok(body) => body,
+        Err(e) => return (None, e.into()),
+    };
+    match serde_json::from_str::<SdpGenericResponse>(&body) {
+        Ok(error) => {
+            tracing::error!(error = ?error, "SDP Error Response");
+            let code = error.response_status.error_code();
+            (Some(code), error.response_status.into())
+        }
+        Err(e) => {
+            tracing::error!(status, error = %e, body = %body, "Failed to parse SDP error response");
+            let mut body = body;
+            if body.len() > ERROR_BODY_LIMIT {
+                let mut end = ERROR_BODY_LIMIT;
+                while !body.is_char_boundary(end) {
+                    end -= 1;
+                }
+                body.truncate(end);
+            }
+            (None, Error::UnexpectedResponse { status, body })
+        }
+    }
+}
+
+/// Decode `bytes` using the charset in `content_type`, UTF-8 if absent.
+/// Unknown charset labels are treated as UTF-8 too.
+fn decode_text(bytes: &[u8], content_type: Option<&str>) -> Result<String, Error> {
+    let charset = content_type
+        .into_iter()
+        .flat_map(|value| value.split(';').skip(1))
+        .filter_map(|param| param.split_once('='))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case("charset"))
+        .map(|(_, value)| value.trim().trim_matches('"'));
+    let encoding = charset
+        .and_then(|label| encoding_rs::Encoding::for_label(label.as_bytes()))
+        .unwrap_or(encoding_rs::UTF_8);
+    encoding
+        .decode_without_bom_handling_and_without_replacement(bytes)
+        .map(|text| text.into_owned())
+        .ok_or_else(|| Error::InvalidValue(format!("attachment is not valid {}", encoding.name())))
+}
+
+/// Reject subjects SDP would refuse with an unhelpful 4001, counted in characters.
+fn check_subject_length(subject: &str, limit: Option<usize>) -> Result<(), Error> {
+    let Some(limit) = limit else {
+        return Ok(());
+    };
+    let length = subject.chars().count();
+    if length > limit {
+        return Err(Error::InvalidValue(format!(
+            "subject too long: {length} characters, limit is {limit}"
+        )));
+    }
+    Ok(())
+}
+
+/// SDP allows merging at most 49 tickets into a target in a single call.
+fn check_merge_count(merge_ids: &[TicketID]) -> Result<(), Error> {
+    if merge_ids.len() > 49 {
+        tracing::warn!("attempted to merge more than 49 tickets");
+        return Err(Error::from_sdp(
+            400,
+            "Cannot merge more than 49 tickets at once".to_string(),
+            None,
+        ));
     }
+    Ok(())
 }
 
 use serde::Deserialize;
@@ -644,8 +1847,8 @@ use serde_json::Value;
 
 use crate::builders::WorklogData;
 use crate::{
-    NoteID, ServiceDesk, TicketID, UserID,
-    error::{Error, SdpErrorMessage},
+    InputDataMode, NoteID, ServiceDesk, TicketID, UserID,
+    error::{Error, FieldError, SdpErrorMessage},
 };
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
@@ -656,6 +1859,10 @@ pub(crate) struct SearchRequest {
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct ListInfo {
     pub row_count: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_index: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub get_total_count: Option<bool>,
     pub search_criteria: Criteria,
 }
 
@@ -715,6 +1922,54 @@ pub enum LogicalOp {
 #[derive(Deserialize, Serialize, Debug, PartialEq)]
 pub struct TicketSearchResponse {
     pub requests: Vec<DetailedTicket>,
+    #[serde(default)]
+    pub list_info: Option<ListInfoResponse>,
+}
+
+/// A single page of search results along with SDP's paging info.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchPage {
+    pub tickets: Vec<DetailedTicket>,
+    /// Total number of matching tickets, if SDP reported it
+    pub total_count: Option<u64>,
+    pub has_more_rows: bool,
+    pub start_index: u32,
+}
+
+/// Summary of a request sitting in the trash.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TrashedTicket {
+    pub id: TicketID,
+    #[serde(default)]
+    pub subject: String,
+    pub status: Option<Status>,
+    #[serde(default, deserialize_with = "deserialize_optional_user_info")]
+    pub requester: Option<UserInfo>,
+    pub deleted_by: Option<UserInfo>,
+    pub deleted_time: Option<TimeEntry>,
+}
+
+#[derive(Deserialize, Debug)]
+struct TemplatesListResponse {
+    #[serde(default)]
+    request_templates: Vec<TemplateInfo>,
+}
+
+#[derive(Deserialize, Debug)]
+struct TemplateDetailsResponse {
+    request_template: Template,
+}
+
+#[derive(Deserialize, Debug)]
+struct StatusesResponse {
+    #[serde(default)]
+    statuses: Vec<Status>,
+}
+
+#[derive(Deserialize, Debug)]
+struct TrashListResponse {
+    #[serde(default)]
+    requests: Vec<TrashedTicket>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
@@ -723,6 +1978,24 @@ pub struct Account {
     pub name: String,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Site {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Department {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct LinkedAccountsResponse {
+    #[serde(default)]
+    accounts: Vec<Account>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 struct DetailedTicketResponse {
     request: DetailedTicket,
@@ -737,7 +2010,9 @@ pub struct DetailedTicket {
     pub subject: String,
     pub description: Option<String>,
     pub status: Status,
+    #[serde(default, deserialize_with = "deserialize_optional_priority")]
     pub priority: Option<Priority>,
+    #[serde(default, deserialize_with = "deserialize_optional_user_info")]
     pub requester: Option<UserInfo>,
     pub technician: Option<UserInfo>,
     #[serde(skip_serializing)]
@@ -748,14 +2023,142 @@ pub struct DetailedTicket {
     pub resolved_time: Option<TimeEntry>,
     pub completed_time: Option<TimeEntry>,
     pub udf_fields: Option<Value>,
+    /// Raw UDF values some instances return next to the display-oriented `udf_fields`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub udf_pipeline_fields: Option<Value>,
+    /// Request-level custom fields some instances keep apart from `udf_fields`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub additional_fields: Option<Value>,
     pub attachments: Option<Vec<Attachment>>,
     pub closure_info: Option<Value>,
-    pub site: Option<Value>,
-    pub department: Option<Value>,
-    pub account: Option<Value>,
+    pub site: Option<Site>,
+    pub department: Option<Department>,
+    pub account: Option<Account>,
+    /// Whether the ticket is in trash. Only set by SDP builds that return
+    /// trashed requests at all, and by search results that include them.
+    #[serde(default, alias = "is_trashed")]
+    pub is_deleted: bool,
 }
 
-#[derive(Serialize, Debug)]
+static NULL_VALUE: Value = Value::Null;
+
+impl DetailedTicket {
+    /// The `udf_fields` object exactly as SDP returned it, or `Value::Null` if absent.
+    pub fn udf_raw(&self) -> &Value {
+        self.udf_fields.as_ref().unwrap_or(&NULL_VALUE)
+    }
+
+    /// Deserialize `udf_fields` into a struct describing the template's UDFs.
+    ///
+    /// Use serde attributes to map SDP keys onto field names, e.g.
+    /// `#[serde(rename = "udf_sline_1202")] alert_id: String`. A ticket without
+    /// UDFs is treated as an empty object, so all-`Option` structs still succeed.
+    pub fn udf_struct<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        fields_struct(&self.udf_fields)
+    }
+
+    /// UDF values normalized into a flat `key -> value` map.
+    ///
+    /// Depending on the instance, SDP returns UDFs in one of several shapes:
+    /// - flat: `{"udf_sline_1": "x"}`
+    /// - wrapped per field: `{"udf_sline_1": {"value": "x", "display_value": "X"}}`
+    /// - nested under `values`: `{"values": {"udf_sline_1": "x"}}`
+    /// - split into `udf_fields` (display) and `udf_pipeline_fields` (raw values)
+    ///
+    /// All of these produce `{"udf_sline_1": "x"}`. When a key appears in both
+    /// `udf_fields` and `udf_pipeline_fields`, the pipeline value wins.
+    pub fn udf_flat(&self) -> HashMap<String, Value> {
+        let mut flat = HashMap::new();
+        flatten_udfs(self.udf_raw(), &mut flat);
+        if let Some(pipeline) = &self.udf_pipeline_fields {
+            flatten_udfs(pipeline, &mut flat);
+        }
+        flat
+    }
+
+    /// Time since the ticket was created, `None` if `created_time` can't be parsed.
+    pub fn age(&self) -> Option<Duration> {
+        self.age_at(Utc::now())
+    }
+
+    /// Like [`age`](Self::age), measured at `now`. Creation times in the future
+    /// (clock skew) give a zero age.
+    pub fn age_at(&self, now: DateTime<Utc>) -> Option<Duration> {
+        let created = self.created_time.as_datetime()?;
+        Some((now - created).to_std().unwrap_or(Duration::ZERO))
+    }
+
+    /// Whether the due-by time has passed without the ticket being resolved.
+    pub fn is_overdue(&self) -> bool {
+        self.is_overdue_at(Utc::now())
+    }
+
+    /// Like [`is_overdue`](Self::is_overdue), checked at `now`. Tickets without
+    /// a parseable due-by time are never overdue.
+    pub fn is_overdue_at(&self, now: DateTime<Utc>) -> bool {
+        let done = self.resolved_time.is_some() || self.completed_time.is_some();
+        !done
+            && self
+                .due_by_time
+                .as_ref()
+                .and_then(TimeEntry::as_datetime)
+                .is_some_and(|due| due < now)
+    }
+
+    /// The `additional_fields` object exactly as SDP returned it, or `Value::Null` if absent.
+    pub fn additional_fields_raw(&self) -> &Value {
+        self.additional_fields.as_ref().unwrap_or(&NULL_VALUE)
+    }
+
+    /// Deserialize `additional_fields` into a struct, like [`udf_struct`](Self::udf_struct).
+    pub fn additional_fields_struct<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        fields_struct(&self.additional_fields)
+    }
+
+    /// `additional_fields` normalized into a flat `key -> value` map, accepting the
+    /// same shapes as [`udf_flat`](Self::udf_flat).
+    pub fn additional_fields_flat(&self) -> HashMap<String, Value> {
+        let mut flat = HashMap::new();
+        flatten_udfs(self.additional_fields_raw(), &mut flat);
+        flat
+    }
+}
+
+/// Deserialize a custom field object, treating an absent one as empty.
+fn fields_struct<T: DeserializeOwned>(fields: &Option<Value>) -> Result<T, Error> {
+    let fields = match fields {
+        Some(Value::Null) | None => Value::Object(Default::default()),
+        Some(fields) => fields.clone(),
+    };
+    Ok(serde_json::from_value(fields)?)
+}
+
+fn flatten_udfs(source: &Value, out: &mut HashMap<String, Value>) {
+    let Some(fields) = source.as_object() else {
+        return;
+    };
+    for (key, value) in fields {
+        match value {
+            Value::Object(nested) if key == "values" => {
+                for (key, value) in nested {
+                    out.insert(key.clone(), unwrap_udf_value(value));
+                }
+            }
+            _ => {
+                out.insert(key.clone(), unwrap_udf_value(value));
+            }
+        }
+    }
+}
+
+fn unwrap_udf_value(value: &Value) -> Value {
+    match value.get("value") {
+        Some(inner) if value.is_object() => inner.clone(),
+        _ => value.clone(),
+    }
+}
+
+#[derive(Serialize, Debug)]
 struct EditTicketRequest<'a> {
     request: &'a EditTicketData,
 }
@@ -776,6 +2179,22 @@ pub struct EditTicketData {
     pub priority: Option<Priority>,
     /// Dynamically defined template fields
     pub udf_fields: Option<Value>,
+    /// Request mode name, e.g. "E-Mail" or "Web Form". Omitted when `None`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_name_object",
+        deserialize_with = "deserialize_optional_name_object"
+    )]
+    pub mode: Option<String>,
+    /// Request type name, e.g. "Incident" or "Service Request". Omitted when `None`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_name_object",
+        deserialize_with = "deserialize_optional_name_object"
+    )]
+    pub request_type: Option<String>,
 }
 
 impl From<DetailedTicket> for EditTicketData {
@@ -787,6 +2206,21 @@ impl From<DetailedTicket> for EditTicketData {
             requester: value.requester,
             priority: value.priority,
             udf_fields: value.udf_fields,
+            mode: None,
+            request_type: None,
+        }
+    }
+}
+
+impl EditTicketData {
+    /// Edit payload that only moves `current` to `new_status`.
+    ///
+    /// Every other field is copied from `current`, since the PUT blanks whatever
+    /// is left out.
+    pub fn status_only(current: &DetailedTicket, new_status: Status) -> Self {
+        Self {
+            status: new_status,
+            ..Self::from(current.clone())
         }
     }
 }
@@ -794,6 +2228,7 @@ impl From<DetailedTicket> for EditTicketData {
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) struct ResponseStatus {
     pub(crate) status: String,
+    #[serde(deserialize_with = "crate::deserialize_status_code")]
     pub(crate) status_code: i64,
 }
 
@@ -927,6 +2362,38 @@ pub const PRIORITY_ID_CRITICAL: u64 = 301;
 //     },
 // ),
 impl Priority {
+    /// Resolve a bare id (`"4"`) or name (`"high"`) to one of the standard priorities.
+    ///
+    /// Unknown values are kept as they are: numeric ones as the id, anything else
+    /// as the name, with the other fields left empty.
+    pub fn from_id_or_name(value: &str) -> Self {
+        let known = [
+            Priority::low(),
+            Priority::medium(),
+            Priority::high(),
+            Priority::critical(),
+        ];
+        if let Some(priority) = known
+            .into_iter()
+            .find(|p| p.id == value || p.name.eq_ignore_ascii_case(value))
+        {
+            return priority;
+        }
+        if value.parse::<u64>().is_ok() {
+            Priority {
+                id: value.to_string(),
+                name: String::new(),
+                color: None,
+            }
+        } else {
+            Priority {
+                id: String::new(),
+                name: value.to_string(),
+                color: None,
+            }
+        }
+    }
+
     pub fn low() -> Self {
         Priority {
             id: PRIORITY_ID_LOW.to_string(),
@@ -960,6 +2427,24 @@ impl Priority {
             color: Some("#8b0808".to_string()),
         }
     }
+
+    /// The next standard level up: Low → Medium → High → Critical, staying at Critical.
+    ///
+    /// Matched by id, or by name when the id is empty. `None` for priorities
+    /// outside the standard four.
+    pub fn escalated(&self) -> Option<Self> {
+        let id = if self.id.is_empty() {
+            Priority::from_id_or_name(&self.name).id
+        } else {
+            self.id.clone()
+        };
+        match id.parse::<u64>().ok()? {
+            PRIORITY_ID_LOW => Some(Priority::medium()),
+            PRIORITY_ID_MEDIUM => Some(Priority::high()),
+            PRIORITY_ID_HIGH | PRIORITY_ID_CRITICAL => Some(Priority::critical()),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -978,6 +2463,61 @@ pub struct UserInfo {
     pub profile_pic: Option<Value>,
 }
 
+#[derive(Debug, Deserialize)]
+struct CurrentUserResponse {
+    user: UserInfo,
+}
+
+/// Build and API version of an SDP instance, see [`ServiceDesk::server_info`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ServerInfo {
+    /// Build number, e.g. 14300 for 14.3
+    #[serde(deserialize_with = "crate::deserialize_string_or_u64")]
+    pub build_number: u64,
+    /// Human readable product version, e.g. "14.3"
+    pub version: Option<String>,
+    /// API version, e.g. "v3"
+    pub api_version: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ServerInfoResponse {
+    build_info: ServerInfo,
+}
+
+#[derive(Debug, Deserialize)]
+struct UsersListResponse {
+    #[serde(default)]
+    users: Vec<UserInfo>,
+}
+
+fn pick_unique_user(query: &str, candidates: Vec<UserInfo>) -> Result<UserInfo, Error> {
+    let is_exact = |user: &UserInfo| {
+        user.name.eq_ignore_ascii_case(query)
+            || user
+                .email_id
+                .as_deref()
+                .is_some_and(|email| email.eq_ignore_ascii_case(query))
+    };
+    let mut matches: Vec<UserInfo> = candidates.iter().filter(|u| is_exact(u)).cloned().collect();
+    if matches.is_empty() {
+        matches = candidates;
+    }
+    match matches.len() {
+        0 => Err(Error::NotFound(format!("no user matches {query:?}"))),
+        1 => Ok(matches.remove(0)),
+        _ => Err(Error::NotUnique(format!(
+            "{query:?} matches {} users: {}",
+            matches.len(),
+            matches
+                .iter()
+                .map(|u| u.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))),
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Resolution {
     pub content: Option<String>,
@@ -1011,6 +2551,14 @@ pub struct TimeEntry {
     pub value: String,
 }
 
+impl TimeEntry {
+    /// `value` parsed as epoch milliseconds, `None` if it isn't a number.
+    pub fn as_datetime(&self) -> Option<DateTime<Utc>> {
+        let millis = self.value.trim().parse::<i64>().ok()?;
+        DateTime::from_timestamp_millis(millis)
+    }
+}
+
 #[derive(Serialize, Debug)]
 struct CreateTicketRequest<'a> {
     request: &'a CreateTicketData,
@@ -1030,6 +2578,10 @@ pub struct CreateTicketData {
     // per template at SDP. They need to be explicitly deserialized by the user
     // after we've converted them to plain serde_json::Value.
     pub udf_fields: Value,
+    /// Request-level custom fields, for instances exposing them apart from UDFs.
+    /// Omitted when `None`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub additional_fields: Option<Value>,
     #[serde(
         serialize_with = "serialize_name_object",
         deserialize_with = "deserialize_name_object"
@@ -1040,6 +2592,22 @@ pub struct CreateTicketData {
         deserialize_with = "deserialize_name_object"
     )]
     pub template: String,
+    /// Request mode name, e.g. "E-Mail" or "Web Form". Omitted when `None`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_name_object",
+        deserialize_with = "deserialize_optional_name_object"
+    )]
+    pub mode: Option<String>,
+    /// Request type name, e.g. "Incident" or "Service Request". Omitted when `None`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_name_object",
+        deserialize_with = "deserialize_optional_name_object"
+    )]
+    pub request_type: Option<String>,
 }
 
 impl Default for CreateTicketData {
@@ -1050,8 +2618,109 @@ impl Default for CreateTicketData {
             requester: String::new(),
             priority: Priority::medium(),
             udf_fields: Value::Null,
+            additional_fields: None,
             account: String::new(),
             template: String::new(),
+            mode: None,
+            request_type: None,
+        }
+    }
+}
+
+impl CreateTicketData {
+    /// Check `udf_fields` against a template's UDF definitions before sending.
+    ///
+    /// Returns [`Error::MissingField`] for an absent or empty mandatory field and
+    /// [`Error::InvalidValue`] when a supplied value doesn't match its field type.
+    /// UDFs not described by the template are left alone.
+    pub fn validate_against(&self, template: &Template) -> Result<(), Error> {
+        for field in &template.udf_fields {
+            let value = self.udf_fields.get(&field.key).filter(|v| match v {
+                Value::Null => false,
+                Value::String(s) => !s.is_empty(),
+                _ => true,
+            });
+            let Some(value) = value else {
+                if field.mandatory {
+                    return Err(Error::MissingField(field.key.clone()));
+                }
+                continue;
+            };
+            if !field.field_type.accepts(value) {
+                return Err(Error::InvalidValue(format!(
+                    "{}: expected {}, got {}",
+                    field.key, field.field_type, value
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Request template metadata, used to validate UDF fields client-side.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Template {
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub udf_fields: Vec<UdfFieldDefinition>,
+    /// Default field values of the template, SDP's `request` object, e.g.
+    /// `{"priority": {...}, "udf_fields": {...}}`. `Null` when unknown.
+    #[serde(default, alias = "request", skip_serializing_if = "Value::is_null")]
+    pub defaults: Value,
+}
+
+/// Definition of a single UDF field on a template.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UdfFieldDefinition {
+    /// Key under `udf_fields`, e.g. `udf_sline_1202`
+    pub key: String,
+    #[serde(default)]
+    pub mandatory: bool,
+    pub field_type: UdfFieldType,
+}
+
+/// Value type expected by a UDF field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UdfFieldType {
+    Text,
+    Numeric,
+    /// Epoch millis, either bare or as an SDP `{ "value": ... }` time object
+    Date,
+    /// One of the allowed options. An empty list accepts any string.
+    Picklist(Vec<String>),
+}
+
+impl UdfFieldType {
+    fn accepts(&self, value: &Value) -> bool {
+        let is_numeric = |v: &Value| match v {
+            Value::Number(_) => true,
+            Value::String(s) => s.parse::<f64>().is_ok(),
+            _ => false,
+        };
+        match self {
+            UdfFieldType::Text => value.is_string(),
+            UdfFieldType::Numeric => is_numeric(value),
+            UdfFieldType::Date => match value.get("value") {
+                Some(inner) => is_numeric(inner),
+                None => is_numeric(value),
+            },
+            UdfFieldType::Picklist(options) => match value.as_str() {
+                Some(choice) => options.is_empty() || options.iter().any(|o| o == choice),
+                None => false,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for UdfFieldType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UdfFieldType::Text => write!(f, "text"),
+            UdfFieldType::Numeric => write!(f, "numeric"),
+            UdfFieldType::Date => write!(f, "date"),
+            UdfFieldType::Picklist(options) => write!(f, "one of [{}]", options.join(", ")),
         }
     }
 }
@@ -1077,6 +2746,115 @@ where
     s.end()
 }
 
+pub(crate) fn deserialize_optional_name_object<'de, D>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct NameObject {
+        name: String,
+    }
+
+    Ok(Option::<NameObject>::deserialize(deserializer)?.map(|n| n.name))
+}
+
+/// Accepts `requester` either as a full user object or as a bare name string,
+/// the string form becomes a [`UserInfo`] with only `name` set.
+pub(crate) fn deserialize_optional_user_info<'de, D>(
+    deserializer: D,
+) -> Result<Option<UserInfo>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NameOrUser {
+        Name(String),
+        User(Box<UserInfo>),
+    }
+
+    Ok(
+        Option::<NameOrUser>::deserialize(deserializer)?.map(|value| match value {
+            NameOrUser::Name(name) => UserInfo {
+                name,
+                ..Default::default()
+            },
+            NameOrUser::User(user) => *user,
+        }),
+    )
+}
+
+/// Accepts a time as a `{value, display_value}` object or as a bare string or
+/// number, which is used for both fields.
+pub(crate) fn deserialize_optional_time_entry<'de, D>(
+    deserializer: D,
+) -> Result<Option<TimeEntry>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum RawOrEntry {
+        Text(String),
+        Number(i64),
+        Entry(TimeEntry),
+    }
+
+    Ok(
+        Option::<RawOrEntry>::deserialize(deserializer)?.map(|value| match value {
+            RawOrEntry::Text(text) => TimeEntry {
+                display_value: text.clone(),
+                value: text,
+            },
+            RawOrEntry::Number(millis) => TimeEntry {
+                display_value: millis.to_string(),
+                value: millis.to_string(),
+            },
+            RawOrEntry::Entry(entry) => entry,
+        }),
+    )
+}
+
+/// Accepts `priority` as a full object or as a bare id/name, see
+/// [`Priority::from_id_or_name`].
+pub(crate) fn deserialize_optional_priority<'de, D>(
+    deserializer: D,
+) -> Result<Option<Priority>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum PriorityRepr {
+        Text(String),
+        Number(u64),
+        Object(Priority),
+    }
+
+    Ok(
+        Option::<PriorityRepr>::deserialize(deserializer)?.map(|value| match value {
+            PriorityRepr::Text(text) => Priority::from_id_or_name(&text),
+            PriorityRepr::Number(id) => Priority::from_id_or_name(&id.to_string()),
+            PriorityRepr::Object(priority) => priority,
+        }),
+    )
+}
+
+pub(crate) fn serialize_optional_name_object<S>(
+    name: &Option<String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match name {
+        Some(name) => serialize_name_object(name, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Serialize, Debug, PartialEq, Eq)]
 pub(crate) struct NameWrapper {
@@ -1127,6 +2905,83 @@ struct ClosureInfo {
     closure_code: String,
 }
 
+/// SLA applied to a ticket.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Sla {
+    pub id: Option<String>,
+    pub name: String,
+    pub due_by_time: Option<TimeEntry>,
+    pub first_response_due_by_time: Option<TimeEntry>,
+    /// Resolution due time has passed
+    #[serde(default)]
+    pub is_overdue: bool,
+    #[serde(default)]
+    pub is_first_response_overdue: bool,
+    #[serde(default)]
+    pub escalation_levels: Vec<SlaEscalation>,
+}
+
+/// One escalation step of an [`Sla`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SlaEscalation {
+    #[serde(deserialize_with = "crate::deserialize_string_or_u64")]
+    pub level: u64,
+    pub escalate_to: Option<UserInfo>,
+    pub escalation_time: Option<TimeEntry>,
+}
+
+#[derive(Deserialize, Debug)]
+struct SlaResponse {
+    sla: Sla,
+}
+
+/// Ticket fields requested when only the attachments are of interest.
+const ATTACHMENT_FIELDS: &[&str] = &["id", "attachments"];
+
+/// Ticket fields requested for the [`ServiceDesk::has_attachments`] check.
+const HAS_ATTACHMENTS_FIELDS: &[&str] = &["id", "has_attachments"];
+
+#[derive(Deserialize, Debug)]
+struct HasAttachmentsResponse {
+    request: HasAttachments,
+}
+
+#[derive(Deserialize, Debug)]
+struct HasAttachments {
+    #[serde(default)]
+    has_attachments: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct AttachmentsOnlyResponse {
+    request: AttachmentsOnly,
+}
+
+#[derive(Deserialize, Debug)]
+struct AttachmentsOnly {
+    attachments: Option<Vec<Attachment>>,
+}
+
+/// Attachment metadata read from response headers, see [`ServiceDesk::attachment_info`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AttachmentMeta {
+    pub content_length: Option<u64>,
+    pub content_type: Option<String>,
+}
+
+/// Closure code configured on the instance, e.g. "Resolved" or "Cancelled".
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ClosureCode {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct ClosureCodesResponse {
+    #[serde(default)]
+    closure_codes: Vec<ClosureCode>,
+}
+
 #[derive(Serialize, Debug)]
 struct AddNoteRequest<'a> {
     note: &'a NoteData,
@@ -1137,13 +2992,93 @@ struct AddWorklogRequest<'a> {
     worklog: &'a WorklogData,
 }
 
-#[derive(Serialize, Debug, Default, PartialEq, Eq)]
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
 pub struct NoteData {
     pub mark_first_response: bool,
     pub add_to_linked_requests: bool,
     pub notify_technician: bool,
     pub show_to_requester: bool,
     pub description: String,
+    /// Ids of files already uploaded to SDP, sent as `attachments`. Omitted when empty.
+    #[serde(
+        rename = "attachments",
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serialize_attachment_refs"
+    )]
+    pub attachment_ids: Vec<String>,
+}
+
+/// Serialize file ids as SDP reference objects, `[{ "id": "123" }]`.
+fn serialize_attachment_refs<S>(ids: &[String], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    #[derive(Serialize)]
+    struct IdRef<'a> {
+        id: &'a str,
+    }
+
+    serializer.collect_seq(ids.iter().map(|id| IdRef { id }))
+}
+
+/// Reply e-mail sent from a ticket, see [`ServiceDesk::reply`].
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct ReplyData {
+    pub subject: String,
+    pub description: String,
+    /// Recipient e-mail addresses
+    #[serde(serialize_with = "serialize_email_refs")]
+    pub to: Vec<String>,
+    /// Omitted when empty.
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serialize_email_refs"
+    )]
+    pub cc: Vec<String>,
+    /// Ids of files already uploaded to SDP, sent as `attachments`. Omitted when empty.
+    #[serde(
+        rename = "attachments",
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serialize_attachment_refs"
+    )]
+    pub attachment_ids: Vec<String>,
+}
+
+/// Serialize e-mail addresses as SDP recipients, `[{ "email_id": "a@b.c" }]`.
+fn serialize_email_refs<S>(emails: &[String], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    #[derive(Serialize)]
+    struct EmailRef<'a> {
+        email_id: &'a str,
+    }
+
+    serializer.collect_seq(emails.iter().map(|email_id| EmailRef { email_id }))
+}
+
+#[derive(Serialize, Debug)]
+struct ReplyRequest<'a> {
+    notification: ReplyNotification<'a>,
+}
+
+#[derive(Serialize, Debug)]
+struct ReplyNotification<'a> {
+    #[serde(rename = "type")]
+    notification_type: &'static str,
+    #[serde(flatten)]
+    reply: &'a ReplyData,
+}
+
+impl<'a> ReplyRequest<'a> {
+    fn new(reply: &'a ReplyData) -> Self {
+        Self {
+            notification: ReplyNotification {
+                notification_type: "reply",
+                reply,
+            },
+        }
+    }
 }
 
 // Note response structures
@@ -1155,11 +3090,16 @@ pub(crate) struct NoteResponse {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct NotesListResponse {
     pub list_info: Option<ListInfoResponse>,
+    #[serde(default)]
     pub notes: Vec<Note>,
     pub response_status: Vec<ResponseStatus>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Paging info echoed back by SDP list endpoints.
+///
+/// Not every endpoint returns every field, missing ones take their default.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ListInfoResponse {
     pub has_more_rows: bool,
     pub page: u32,
@@ -1167,6 +3107,8 @@ pub struct ListInfoResponse {
     pub sort_field: String,
     pub sort_order: String,
     pub start_index: u32,
+    /// Only present when the request asked for `get_total_count`
+    pub total_count: Option<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -1182,8 +3124,13 @@ pub struct Note {
     pub notify_technician: bool,
     #[serde(default)]
     pub add_to_linked_requests: bool,
+    /// Older builds send a bare string, kept as both `value` and `display_value`.
+    #[serde(default, deserialize_with = "deserialize_optional_time_entry")]
     pub created_time: Option<TimeEntry>,
+    /// Older builds send only the author's name as a string.
+    #[serde(default, deserialize_with = "deserialize_optional_user_info")]
     pub created_by: Option<UserInfo>,
+    #[serde(default, deserialize_with = "deserialize_optional_time_entry")]
     pub last_updated_time: Option<TimeEntry>,
 }
 
@@ -1203,22 +3150,152 @@ struct ConversationsResponse {
     conversations: Vec<ConversationSummary>,
 }
 
-#[derive(Debug, PartialEq, Eq, Deserialize)]
-struct ConversationSummary {
-    #[serde(default)]
-    has_attachments: bool,
+/// A single email or notification from a ticket's conversation history.
+///
+/// The full body is behind [`content_url`](Self::content_url), see
+/// [`ServiceDesk::get_conversation_content`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Conversation {
+    pub id: Option<String>,
+    pub subject: Option<String>,
+    #[serde(rename = "type")]
+    pub conversation_type: Option<String>,
+    pub from: Option<Value>,
+    pub sent_time: Option<TimeEntry>,
+    pub created_time: Option<TimeEntry>,
     #[serde(default)]
-    content_url: Option<String>,
+    pub has_attachments: bool,
+    pub content_url: Option<String>,
 }
 
-fn normalize_attachment_url(base_url: &reqwest::Url, value: &str) -> Result<String, Error> {
-    Ok(base_url.join(value)?.to_string())
+impl Conversation {
+    /// Sent time, falling back to the creation time.
+    pub fn time(&self) -> Option<&TimeEntry> {
+        self.sent_time.as_ref().or(self.created_time.as_ref())
+    }
 }
 
-#[derive(Serialize, Debug, PartialEq, Eq)]
-struct NotesListInfo {
-    row_count: u32,
-    start_index: u32,
+#[derive(Debug, Deserialize)]
+struct ConversationListResponse {
+    #[serde(default)]
+    conversations: Vec<Conversation>,
+}
+
+/// One entry of a ticket's transcript, see [`ServiceDesk::thread`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ThreadItem {
+    Note(Note),
+    Conversation(Conversation),
+}
+
+impl ThreadItem {
+    /// Time of the entry in epoch millis, if SDP reported one.
+    pub fn timestamp_millis(&self) -> Option<i64> {
+        let time = match self {
+            ThreadItem::Note(note) => note.created_time.as_ref(),
+            ThreadItem::Conversation(conversation) => conversation.time(),
+        }?;
+        time.value.parse().ok()
+    }
+}
+
+fn merge_thread(notes: Vec<Note>, conversations: Vec<Conversation>) -> Vec<ThreadItem> {
+    let mut items: Vec<ThreadItem> = notes
+        .into_iter()
+        .map(ThreadItem::Note)
+        .chain(conversations.into_iter().map(ThreadItem::Conversation))
+        .collect();
+    // `None` sorts before `Some`, so key on the missing flag first to push those last.
+    items.sort_by_key(|item| {
+        let time = item.timestamp_millis();
+        (time.is_none(), time)
+    });
+    items
+}
+
+/// Lightweight overview of a conversation, without its content.
+///
+/// See [`ServiceDesk::conversation_summaries`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(from = "RawConversationSummary")]
+pub struct ConversationSummary {
+    pub id: Option<String>,
+    pub subject: Option<String>,
+    pub has_attachments: bool,
+    /// Taken from `attachment_count`, or counted from the `attachments` list
+    /// when SDP only sends that. 0 when neither is present.
+    pub attachment_count: usize,
+    /// Sent time, falling back to the creation time
+    pub time: Option<TimeEntry>,
+    pub content_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawConversationSummary {
+    id: Option<String>,
+    subject: Option<String>,
+    #[serde(default)]
+    has_attachments: bool,
+    attachment_count: Option<usize>,
+    #[serde(default)]
+    attachments: Vec<Value>,
+    sent_time: Option<TimeEntry>,
+    created_time: Option<TimeEntry>,
+    #[serde(default)]
+    content_url: Option<String>,
+}
+
+impl From<RawConversationSummary> for ConversationSummary {
+    fn from(raw: RawConversationSummary) -> Self {
+        Self {
+            id: raw.id,
+            subject: raw.subject,
+            has_attachments: raw.has_attachments,
+            attachment_count: raw.attachment_count.unwrap_or(raw.attachments.len()),
+            time: raw.sent_time.or(raw.created_time),
+            content_url: raw.content_url,
+        }
+    }
+}
+
+/// Keys a conversation's content can be wrapped in, depending on its type.
+const CONVERSATION_CONTENT_KEYS: &[&str] = &["notification", "note", "request"];
+
+/// Collect the attachments of a conversation's content, whichever of
+/// [`CONVERSATION_CONTENT_KEYS`] they sit under. Attachments found under
+/// several keys are kept once, by id.
+fn conversation_attachments(content: &Value) -> Result<Vec<Attachment>, Error> {
+    let mut attachments: Vec<Attachment> = Vec::new();
+    for key in CONVERSATION_CONTENT_KEYS {
+        let Some(found) = content
+            .get(key)
+            .and_then(|c| c.get("attachments"))
+            .filter(|found| !found.is_null())
+        else {
+            continue;
+        };
+        let found: Vec<Attachment> = serde_json::from_value(found.clone())?;
+        for attachment in found {
+            if !attachments.iter().any(|a| a.id == attachment.id) {
+                attachments.push(attachment);
+            }
+        }
+    }
+    Ok(attachments)
+}
+
+fn normalize_attachment_url(base_url: &reqwest::Url, value: &str) -> Result<String, Error> {
+    Ok(base_url.join(value)?.to_string())
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+struct NotesListInfo {
+    row_count: u32,
+    start_index: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sort_field: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sort_order: Option<String>,
 }
 
 #[derive(Serialize, Debug, PartialEq, Eq)]
@@ -1245,6 +3322,153 @@ struct MergeRequestId {
     id: String,
 }
 
+/// Problem or change a request was linked to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssociatedItem {
+    pub id: String,
+    pub title: Option<String>,
+}
+
+/// Outcome of [`ServiceDesk::associate_problem`] and [`ServiceDesk::associate_change`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Association {
+    /// The link was created
+    Linked(AssociatedItem),
+    /// The request was already linked, SDP answered 4005
+    AlreadyLinked,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssociationResponse {
+    #[serde(alias = "problem", alias = "change")]
+    associated: AssociatedItem,
+}
+
+/// Confirmation SDP sends back for a close, assign or edit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationResult {
+    /// SDP status code, `2000` on success
+    pub status_code: u32,
+    /// First message of the `response_status`, if SDP sent one with text
+    pub message: Option<String>,
+    /// When the request was last updated, if SDP echoed it back
+    pub updated_time: Option<TimeEntry>,
+}
+
+/// Response to a ticket operation: the status, plus the updated request on
+/// builds that return it.
+#[derive(Debug, Deserialize)]
+struct OperationResponse {
+    response_status: SdpResponseStatus,
+    #[serde(default)]
+    request: Option<UpdatedRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdatedRequest {
+    #[serde(default, deserialize_with = "deserialize_optional_time_entry")]
+    last_updated_time: Option<TimeEntry>,
+}
+
+impl OperationResponse {
+    fn result(&self) -> OperationResult {
+        let message = self
+            .response_status
+            .messages
+            .iter()
+            .flatten()
+            .next()
+            .map(|m| m.message.clone().into_text())
+            .filter(|text| !text.is_empty());
+        OperationResult {
+            status_code: self.response_status.status_code,
+            message,
+            updated_time: self
+                .request
+                .as_ref()
+                .and_then(|request| request.last_updated_time.clone()),
+        }
+    }
+}
+
+/// Outcome of a merge, SDP reports success per merged ticket.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeResult {
+    pub merged: Vec<TicketID>,
+    /// Tickets that were not merged, with SDP's reason; requested tickets
+    /// missing from SDP's response are listed here too
+    pub failed: Vec<(TicketID, String)>,
+}
+
+/// SDP answers a merge with either a single status object or one status
+/// entry per requested ticket.
+#[derive(Debug, Deserialize)]
+struct MergeResponse {
+    response_status: OneOrMany<MergeStatusEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+#[derive(Debug, Deserialize)]
+struct MergeStatusEntry {
+    #[serde(default)]
+    id: Option<TicketID>,
+    #[serde(flatten)]
+    status: SdpResponseStatus,
+}
+
+impl MergeResponse {
+    fn into_result(self, merge_ids: &[TicketID]) -> Result<MergeResult, Error> {
+        let entries = match self.response_status {
+            OneOrMany::One(entry) if entry.id.is_none() => {
+                // A single overall status covers every requested ticket.
+                if entry.status.status_code != 2000 {
+                    return Err(entry.status.into());
+                }
+                return Ok(MergeResult {
+                    merged: merge_ids.to_vec(),
+                    failed: vec![],
+                });
+            }
+            OneOrMany::One(entry) => vec![entry],
+            OneOrMany::Many(entries) => entries,
+        };
+
+        let mut result = MergeResult::default();
+        for (position, entry) in entries.into_iter().enumerate() {
+            // Entries without an id answer the requested ticket at the same position.
+            let Some(id) = entry.id.or_else(|| merge_ids.get(position).copied()) else {
+                if entry.status.status_code != 2000 {
+                    return Err(entry.status.into());
+                }
+                continue;
+            };
+            if entry.status.status_code == 2000 {
+                result.merged.push(id);
+            } else {
+                result
+                    .failed
+                    .push((id, Error::from(entry.status).to_string()));
+            }
+        }
+        for &id in merge_ids {
+            let reported = result.merged.contains(&id)
+                || result.failed.iter().any(|(failed, _)| *failed == id);
+            if !reported {
+                result
+                    .failed
+                    .push((id, "not reported in SDP's merge response".to_string()));
+            }
+        }
+        Ok(result)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct TicketResponse {
     pub(crate) request: TicketData,
@@ -1257,10 +3481,14 @@ pub struct TicketData {
     pub subject: String,
     pub description: Option<String>,
     pub status: Status,
+    #[serde(default, deserialize_with = "deserialize_optional_priority")]
     pub priority: Option<Priority>,
     pub created_time: TimeEntry,
+    #[serde(default, deserialize_with = "deserialize_optional_user_info")]
     pub requester: Option<UserInfo>,
     pub account: Account,
+    pub site: Option<Site>,
+    pub department: Option<Department>,
     pub template: TemplateInfo,
     pub udf_fields: Option<Value>,
 }
@@ -1276,6 +3504,186 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn encode_input_data_matches_form_body() {
+        let body = json!({
+            "list_info": {
+                "row_count": 10,
+                "search_criteria": { "field": "subject", "condition": "contains", "value": "a&b c" },
+            },
+        });
+
+        let request = reqwest::Client::new()
+            .post("http://localhost/")
+            .form(&[("input_data", serde_json::to_string(&body).unwrap())])
+            .build()
+            .unwrap();
+        let form_body = request.body().and_then(|b| b.as_bytes()).unwrap();
+
+        let encoded = encode_input_data(&body).unwrap();
+        assert_eq!(encoded.as_bytes(), form_body);
+        assert!(encoded.starts_with("input_data=%7B"));
+    }
+
+    #[test]
+    fn encoding_failure_names_the_request() {
+        let source = serde_urlencoded::ser::Error::Custom("unsupported value".into());
+        let error = with_encoding_context(
+            Error::FormEncoding {
+                context: "encoding input_data".to_string(),
+                source,
+            },
+            &Method::PUT,
+            "/api/v3/requests/42/merge_requests",
+        );
+
+        match &error {
+            Error::FormEncoding { context, .. } => assert_eq!(
+                context,
+                "encoding input_data for PUT /api/v3/requests/42/merge_requests"
+            ),
+            other => panic!("expected FormEncoding, got {other:?}"),
+        }
+        assert!(error.to_string().contains("unsupported value"));
+    }
+
+    fn client_with_mode(input_data_mode: Option<InputDataMode>) -> ServiceDesk {
+        ServiceDesk::new(
+            reqwest::Url::parse("https://sdp.example.com").unwrap(),
+            crate::Credentials::Token {
+                token: "token".into(),
+            },
+            crate::ServiceDeskOptions {
+                input_data_mode,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn input_data_modes_place_body_in_query_or_form() {
+        let body = json!({ "list_info": { "row_count": 1 } });
+        let expected = encode_input_data(&body).unwrap();
+        let client = client_with_mode(None);
+
+        let query = client
+            .build_encoded(InputDataMode::Query, Method::GET, "/api/v3/requests", &body)
+            .unwrap();
+        assert_eq!(query.url().query(), Some(expected.as_str()));
+        assert!(query.body().is_none());
+
+        let form = client
+            .build_encoded(InputDataMode::Form, Method::PUT, "/api/v3/requests", &body)
+            .unwrap();
+        assert_eq!(form.url().query(), None);
+        assert_eq!(
+            form.body().and_then(|b| b.as_bytes()),
+            Some(expected.as_bytes())
+        );
+        assert_eq!(
+            form.headers()[reqwest::header::CONTENT_TYPE],
+            "application/x-www-form-urlencoded"
+        );
+    }
+
+    #[test]
+    fn input_data_mode_override_wins_over_operation_default() {
+        let body = json!({ "list_info": { "row_count": 1 } });
+        let client = client_with_mode(Some(InputDataMode::Form));
+
+        let request = client
+            .build_encoded(InputDataMode::Query, Method::GET, "/api/v3/requests", &body)
+            .unwrap();
+
+        assert_eq!(request.url().query(), None);
+        assert!(request.body().is_some());
+    }
+
+    fn sample_template() -> Template {
+        Template {
+            id: Some("1".to_string()),
+            name: "SOC-with-alert-id".to_string(),
+            udf_fields: vec![
+                UdfFieldDefinition {
+                    key: "udf_sline_1".to_string(),
+                    mandatory: true,
+                    field_type: UdfFieldType::Text,
+                },
+                UdfFieldDefinition {
+                    key: "udf_long_2".to_string(),
+                    mandatory: false,
+                    field_type: UdfFieldType::Numeric,
+                },
+                UdfFieldDefinition {
+                    key: "udf_date_3".to_string(),
+                    mandatory: false,
+                    field_type: UdfFieldType::Date,
+                },
+                UdfFieldDefinition {
+                    key: "udf_pick_4".to_string(),
+                    mandatory: false,
+                    field_type: UdfFieldType::Picklist(vec!["Low".into(), "High".into()]),
+                },
+            ],
+            defaults: Value::Null,
+        }
+    }
+
+    #[test]
+    fn validate_against_accepts_matching_udfs() {
+        let data = CreateTicketData {
+            udf_fields: json!({
+                "udf_sline_1": "alert-1",
+                "udf_long_2": "42",
+                "udf_date_3": { "value": "1700000000000" },
+                "udf_pick_4": "High",
+                "udf_other": [1, 2],
+            }),
+            ..Default::default()
+        };
+        assert!(data.validate_against(&sample_template()).is_ok());
+    }
+
+    #[test]
+    fn validate_against_reports_missing_and_mistyped_udfs() {
+        let missing = CreateTicketData {
+            udf_fields: json!({ "udf_sline_1": "" }),
+            ..Default::default()
+        };
+        assert!(matches!(
+            missing.validate_against(&sample_template()),
+            Err(Error::MissingField(key)) if key == "udf_sline_1"
+        ));
+
+        let numeric = CreateTicketData {
+            udf_fields: json!({ "udf_sline_1": "a", "udf_long_2": "forty" }),
+            ..Default::default()
+        };
+        assert!(matches!(
+            numeric.validate_against(&sample_template()),
+            Err(Error::InvalidValue(msg)) if msg.starts_with("udf_long_2")
+        ));
+
+        let picklist = CreateTicketData {
+            udf_fields: json!({ "udf_sline_1": "a", "udf_pick_4": "Medium" }),
+            ..Default::default()
+        };
+        assert!(matches!(
+            picklist.validate_against(&sample_template()),
+            Err(Error::InvalidValue(msg)) if msg.starts_with("udf_pick_4")
+        ));
+
+        let date = CreateTicketData {
+            udf_fields: json!({ "udf_sline_1": "a", "udf_date_3": "yesterday" }),
+            ..Default::default()
+        };
+        assert!(matches!(
+            date.validate_against(&sample_template()),
+            Err(Error::InvalidValue(_))
+        ));
+    }
+
     #[test]
     fn criteria_default() {
         let criteria = Criteria::default();
@@ -1306,8 +3714,11 @@ mod tests {
             requester: "NETXP".to_string(),
             priority: Priority::high(),
             udf_fields: json!({}),
+            additional_fields: None,
             account: "SOC".to_string(),
             template: "SOC-with-alert-id".to_string(),
+            mode: None,
+            request_type: None,
         };
 
         let serialized = serde_json::to_value(&data).unwrap();
@@ -1342,6 +3753,8 @@ mod tests {
             }),
             priority: Some(Priority::high()),
             udf_fields: None,
+            mode: None,
+            request_type: None,
         };
 
         let serialized = serde_json::to_value(&data).unwrap();
@@ -1350,5 +3763,733 @@ mod tests {
         assert_eq!(serialized["priority"]["name"], "High");
         assert!(serialized["description"].is_null());
         assert_eq!(serialized["status"]["name"], "Open");
+        assert!(serialized.get("mode").is_none());
+        assert!(serialized.get("request_type").is_none());
+    }
+
+    #[test]
+    fn create_ticket_data_serializes_mode_and_request_type() {
+        let data = CreateTicketData {
+            mode: Some("E-Mail".to_string()),
+            request_type: Some("Incident".to_string()),
+            ..Default::default()
+        };
+
+        let serialized = serde_json::to_value(&data).unwrap();
+
+        assert_eq!(serialized["mode"], json!({ "name": "E-Mail" }));
+        assert_eq!(serialized["request_type"], json!({ "name": "Incident" }));
+
+        let unset = serde_json::to_value(CreateTicketData::default()).unwrap();
+        assert!(unset.get("mode").is_none());
+        assert!(unset.get("request_type").is_none());
+    }
+
+    #[test]
+    fn edit_ticket_data_serializes_mode_and_request_type() {
+        let data = EditTicketData {
+            subject: "test".to_string(),
+            status: Status::open(),
+            description: None,
+            requester: None,
+            priority: None,
+            udf_fields: None,
+            mode: Some("Web Form".to_string()),
+            request_type: Some("Service Request".to_string()),
+        };
+
+        let serialized = serde_json::to_value(&data).unwrap();
+
+        assert_eq!(serialized["mode"], json!({ "name": "Web Form" }));
+        assert_eq!(
+            serialized["request_type"],
+            json!({ "name": "Service Request" })
+        );
+        let roundtrip: EditTicketData = serde_json::from_value(serialized).unwrap();
+        assert_eq!(roundtrip, data);
+    }
+
+    #[derive(Deserialize)]
+    struct RequesterOnly {
+        #[serde(default, deserialize_with = "deserialize_optional_user_info")]
+        requester: Option<UserInfo>,
+    }
+
+    #[test]
+    fn requester_deserializes_from_plain_string() {
+        let parsed: RequesterOnly =
+            serde_json::from_value(json!({ "requester": "John Doe" })).unwrap();
+
+        let requester = parsed.requester.unwrap();
+        assert_eq!(requester.name, "John Doe");
+        assert_eq!(requester.id, UserID::default());
+    }
+
+    #[test]
+    fn requester_deserializes_from_object() {
+        let parsed: RequesterOnly = serde_json::from_value(json!({
+            "requester": { "id": "42", "name": "John Doe", "email_id": "john@example.com" },
+        }))
+        .unwrap();
+
+        let requester = parsed.requester.unwrap();
+        assert_eq!(requester.id, UserID("42".to_string()));
+        assert_eq!(requester.email_id.as_deref(), Some("john@example.com"));
+    }
+
+    #[test]
+    fn requester_missing_or_null_is_none() {
+        let missing: RequesterOnly = serde_json::from_value(json!({})).unwrap();
+        let null: RequesterOnly = serde_json::from_value(json!({ "requester": null })).unwrap();
+        assert!(missing.requester.is_none());
+        assert!(null.requester.is_none());
+    }
+
+    fn time_entry(millis: i64) -> TimeEntry {
+        TimeEntry {
+            display_value: String::new(),
+            value: millis.to_string(),
+        }
+    }
+
+    #[test]
+    fn merge_thread_sorts_by_time_with_missing_last() {
+        let note = |id: u64, time: Option<i64>| Note {
+            id: NoteID(id),
+            description: String::new(),
+            show_to_requester: false,
+            mark_first_response: false,
+            notify_technician: false,
+            add_to_linked_requests: false,
+            created_time: time.map(time_entry),
+            created_by: None,
+            last_updated_time: None,
+        };
+        let conversation = |id: &str, sent: Option<i64>, created: Option<i64>| Conversation {
+            id: Some(id.to_string()),
+            subject: None,
+            conversation_type: None,
+            from: None,
+            sent_time: sent.map(time_entry),
+            created_time: created.map(time_entry),
+            has_attachments: false,
+            content_url: None,
+        };
+
+        let thread = merge_thread(
+            vec![note(1, Some(300)), note(2, None), note(3, Some(100))],
+            vec![
+                conversation("a", Some(200), None),
+                conversation("b", None, Some(400)),
+                conversation("c", None, None),
+            ],
+        );
+
+        let order: Vec<String> = thread
+            .iter()
+            .map(|item| match item {
+                ThreadItem::Note(note) => format!("note {}", note.id),
+                ThreadItem::Conversation(c) => format!("conversation {}", c.id.as_deref().unwrap()),
+            })
+            .collect();
+        assert_eq!(
+            order,
+            [
+                "note 3",
+                "conversation a",
+                "note 1",
+                "conversation b",
+                "note 2",
+                "conversation c",
+            ]
+        );
+    }
+
+    #[test]
+    fn subject_length_limit_is_inclusive() {
+        assert!(check_subject_length(&"a".repeat(250), Some(250)).is_ok());
+        assert!(matches!(
+            check_subject_length(&"a".repeat(251), Some(250)),
+            Err(Error::InvalidValue(msg)) if msg.starts_with("subject too long")
+        ));
+    }
+
+    #[test]
+    fn subject_length_counts_characters_not_bytes() {
+        assert!(check_subject_length(&"ż".repeat(10), Some(10)).is_ok());
+        assert!(check_subject_length(&"ż".repeat(11), Some(10)).is_err());
+    }
+
+    #[test]
+    fn subject_length_unlimited_when_none() {
+        assert!(check_subject_length(&"a".repeat(10_000), None).is_ok());
+    }
+
+    #[test]
+    fn sla_deserializes_due_times_and_escalations() {
+        let resp: SlaResponse = serde_json::from_value(json!({
+            "sla": {
+                "id": "3",
+                "name": "High SLA",
+                "due_by_time": { "display_value": "", "value": "1700003600000" },
+                "is_overdue": true,
+                "escalation_levels": [
+                    { "level": "1", "escalate_to": { "id": "7", "name": "Team Lead" } },
+                    { "level": 2 },
+                ],
+            },
+            "response_status": { "status_code": 2000, "status": "success" },
+        }))
+        .unwrap();
+
+        let sla = resp.sla;
+        assert_eq!(sla.name, "High SLA");
+        assert_eq!(sla.due_by_time.unwrap().value, "1700003600000");
+        assert!(sla.is_overdue);
+        assert!(!sla.is_first_response_overdue);
+        assert_eq!(sla.escalation_levels[0].level, 1);
+        assert_eq!(
+            sla.escalation_levels[0].escalate_to.as_ref().unwrap().name,
+            "Team Lead"
+        );
+        assert_eq!(sla.escalation_levels[1].level, 2);
+    }
+
+    #[derive(Deserialize)]
+    struct PriorityOnly {
+        #[serde(default, deserialize_with = "deserialize_optional_priority")]
+        priority: Option<Priority>,
+    }
+
+    fn parse_priority(value: Value) -> Option<Priority> {
+        serde_json::from_value::<PriorityOnly>(json!({ "priority": value }))
+            .unwrap()
+            .priority
+    }
+
+    #[test]
+    fn priority_deserializes_from_object() {
+        let priority = parse_priority(json!({ "id": "4", "name": "High", "color": "#ff5e00" }));
+        assert_eq!(priority, Some(Priority::high()));
+    }
+
+    #[test]
+    fn priority_deserializes_from_bare_id_or_name() {
+        assert_eq!(parse_priority(json!("4")), Some(Priority::high()));
+        assert_eq!(parse_priority(json!(301)), Some(Priority::critical()));
+        assert_eq!(parse_priority(json!("low")), Some(Priority::low()));
+        assert_eq!(parse_priority(Value::Null), None);
+    }
+
+    #[test]
+    fn reply_serializes_recipients_and_attachments() {
+        let reply = ReplyData {
+            subject: "Re: VPN access".into(),
+            description: "Config attached".into(),
+            to: vec!["jane@example.com".into()],
+            cc: vec![],
+            attachment_ids: vec!["901".into(), "902".into()],
+        };
+
+        let serialized = serde_json::to_value(ReplyRequest::new(&reply)).unwrap();
+
+        assert_eq!(
+            serialized,
+            json!({
+                "notification": {
+                    "type": "reply",
+                    "subject": "Re: VPN access",
+                    "description": "Config attached",
+                    "to": [{ "email_id": "jane@example.com" }],
+                    "attachments": [{ "id": "901" }, { "id": "902" }],
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn note_accepts_object_and_string_author_and_time() {
+        let note: Note = serde_json::from_value(json!({
+            "id": "5",
+            "created_by": { "id": "7", "name": "Jane Doe" },
+            "created_time": { "display_value": "Nov 14, 2023", "value": "1700000000000" },
+        }))
+        .unwrap();
+        assert_eq!(note.created_by.unwrap().id, UserID("7".into()));
+        assert_eq!(note.created_time.unwrap().value, "1700000000000");
+
+        let note: Note = serde_json::from_value(json!({
+            "id": "5",
+            "created_by": "Jane Doe",
+            "created_time": "1700000000000",
+            "last_updated_time": 1700000001000i64,
+        }))
+        .unwrap();
+        let author = note.created_by.unwrap();
+        assert_eq!(author.name, "Jane Doe");
+        assert_eq!(author.id, UserID::default());
+        assert_eq!(
+            note.created_time,
+            Some(TimeEntry {
+                display_value: "1700000000000".into(),
+                value: "1700000000000".into(),
+            })
+        );
+        assert_eq!(note.last_updated_time.unwrap().value, "1700000001000");
+
+        let note: Note = serde_json::from_value(json!({ "id": "5", "created_by": null })).unwrap();
+        assert!(note.created_by.is_none());
+        assert!(note.created_time.is_none());
+    }
+
+    #[test]
+    fn priority_escalation_progression() {
+        assert_eq!(Priority::low().escalated(), Some(Priority::medium()));
+        assert_eq!(Priority::medium().escalated(), Some(Priority::high()));
+        assert_eq!(Priority::high().escalated(), Some(Priority::critical()));
+        assert_eq!(Priority::critical().escalated(), Some(Priority::critical()));
+        assert_eq!(
+            Priority::from_id_or_name("medium").escalated(),
+            Some(Priority::high())
+        );
+        assert_eq!(
+            Priority {
+                id: String::new(),
+                name: "low".into(),
+                color: None,
+            }
+            .escalated(),
+            Some(Priority::medium())
+        );
+        assert_eq!(Priority::from_id_or_name("Urgent").escalated(), None);
+        assert_eq!(Priority::from_id_or_name("42").escalated(), None);
+    }
+
+    #[test]
+    fn priority_keeps_unknown_id_or_name() {
+        let unknown_id = parse_priority(json!("902")).unwrap();
+        assert_eq!(unknown_id.id, "902");
+        assert!(unknown_id.name.is_empty());
+
+        let unknown_name = parse_priority(json!("P1 - Outage")).unwrap();
+        assert_eq!(unknown_name.name, "P1 - Outage");
+        assert!(unknown_name.id.is_empty());
+    }
+
+    #[test]
+    fn decode_text_defaults_to_utf8() {
+        let text = decode_text("zażółć gęślą".as_bytes(), None).unwrap();
+        assert_eq!(text, "zażółć gęślą");
+
+        let text = decode_text("a,b\n".as_bytes(), Some("text/csv")).unwrap();
+        assert_eq!(text, "a,b\n");
+    }
+
+    #[test]
+    fn decode_text_uses_content_type_charset() {
+        // "café" in ISO-8859-1
+        let latin1 = [0x63, 0x61, 0x66, 0xe9];
+        let text = decode_text(&latin1, Some("text/plain; charset=\"ISO-8859-1\"")).unwrap();
+        assert_eq!(text, "café");
+    }
+
+    #[test]
+    fn decode_text_rejects_invalid_bytes() {
+        let latin1 = [0x63, 0x61, 0x66, 0xe9];
+        assert!(matches!(
+            decode_text(&latin1, Some("text/plain; charset=utf-8")),
+            Err(Error::InvalidValue(_))
+        ));
+    }
+
+    fn ticket_with_udfs(udf_fields: Value, udf_pipeline_fields: Option<Value>) -> DetailedTicket {
+        let mut ticket = json!({
+            "id": "1",
+            "subject": "udf",
+            "status": { "id": "2", "name": "Open", "color": null },
+            "created_by": { "id": "1", "name": "admin" },
+            "created_time": { "display_value": "", "value": "1700000000000" },
+            "udf_fields": udf_fields,
+        });
+        if let Some(pipeline) = udf_pipeline_fields {
+            ticket["udf_pipeline_fields"] = pipeline;
+        }
+        serde_json::from_value(ticket).unwrap()
+    }
+
+    #[test]
+    fn status_codes_accept_numbers_and_strings() {
+        for code in [json!(4002), json!("4002")] {
+            let status: SdpResponseStatus = serde_json::from_value(json!({
+                "status_code": code,
+                "status": "failed",
+                "messages": [{ "status_code": code, "type": "failed", "message": "denied" }],
+            }))
+            .unwrap();
+            assert_eq!(status.status_code, 4002);
+            assert!(matches!(Error::from(status), Error::Forbidden(_)));
+        }
+        for code in [json!(2000), json!("2000")] {
+            let status: ResponseStatus =
+                serde_json::from_value(json!({ "status_code": code, "status": "success" }))
+                    .unwrap();
+            assert_eq!(status.status_code, 2000);
+        }
+        assert!(
+            serde_json::from_value::<ResponseStatus>(
+                json!({ "status_code": "ok", "status": "success" })
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn error_code_skips_successful_messages() {
+        let status: SdpResponseStatus = serde_json::from_value(json!({
+            "status_code": 4000,
+            "status": "failed",
+            "messages": [
+                { "status_code": 2000, "type": "success", "message": "subject" },
+                { "status_code": 4018, "type": "failed", "message": "request is locked" },
+            ],
+        }))
+        .unwrap();
+        assert_eq!(status.error_code(), 4018);
+
+        let bare: SdpResponseStatus =
+            serde_json::from_value(json!({ "status_code": 4000, "status": "failed" })).unwrap();
+        assert_eq!(bare.error_code(), 4000);
+    }
+
+    #[test]
+    fn additional_fields_accessors() {
+        #[derive(Deserialize)]
+        struct Extra {
+            region: String,
+            cost_center: Option<Value>,
+        }
+
+        let ticket = ticket_with_udfs(json!({}), None);
+        assert_eq!(ticket.additional_fields_raw(), &Value::Null);
+        assert!(ticket.additional_fields_flat().is_empty());
+
+        let ticket: DetailedTicket = serde_json::from_value(json!({
+            "id": "1",
+            "subject": "extra",
+            "status": { "id": "2", "name": "Open", "color": null },
+            "created_by": { "id": "1", "name": "admin" },
+            "created_time": { "display_value": "", "value": "1700000000000" },
+            "udf_fields": null,
+            "additional_fields": {
+                "cost_center": { "value": "CC-42", "display_value": "Finance" },
+                "region": "EMEA",
+            },
+        }))
+        .unwrap();
+
+        let flat = ticket.additional_fields_flat();
+        assert_eq!(flat["cost_center"], json!("CC-42"));
+        assert_eq!(flat["region"], json!("EMEA"));
+        assert_eq!(ticket.additional_fields_raw()["region"], json!("EMEA"));
+        assert!(ticket.udf_flat().is_empty());
+        let extra: Extra = ticket.additional_fields_struct().unwrap();
+        assert_eq!(extra.region, "EMEA");
+        assert!(extra.cost_center.is_some());
+
+        let data = CreateTicketData {
+            additional_fields: Some(json!({ "region": "EMEA" })),
+            ..Default::default()
+        };
+        let serialized = serde_json::to_value(&data).unwrap();
+        assert_eq!(serialized["additional_fields"], json!({ "region": "EMEA" }));
+        let serialized = serde_json::to_value(CreateTicketData::default()).unwrap();
+        assert!(serialized.get("additional_fields").is_none());
+    }
+
+    #[test]
+    fn age_and_overdue_from_fixed_times() {
+        let mut ticket = ticket_with_udfs(json!({}), None);
+        let created = DateTime::from_timestamp_millis(1_700_000_000_000).unwrap();
+        let now = created + chrono::Duration::hours(3);
+
+        assert_eq!(ticket.created_time.as_datetime(), Some(created));
+        assert_eq!(ticket.age_at(now), Some(Duration::from_secs(3 * 3600)));
+        assert_eq!(
+            ticket.age_at(created - chrono::Duration::minutes(1)),
+            Some(Duration::ZERO)
+        );
+
+        assert!(!ticket.is_overdue_at(now));
+        ticket.due_by_time = Some(TimeEntry {
+            display_value: String::new(),
+            value: "1700003600000".into(),
+        });
+        assert!(ticket.is_overdue_at(now));
+        assert!(!ticket.is_overdue_at(created));
+        ticket.resolved_time = ticket.due_by_time.clone();
+        assert!(!ticket.is_overdue_at(now));
+
+        ticket.created_time.value = "not a time".into();
+        assert_eq!(ticket.age_at(now), None);
+    }
+
+    #[test]
+    fn status_only_keeps_other_fields() {
+        let mut ticket = ticket_with_udfs(json!({ "udf_sline_1": "alert-1" }), None);
+        ticket.requester =
+            Some(serde_json::from_value(json!({ "id": "7", "name": "Jane Doe" })).unwrap());
+
+        let edit = EditTicketData::status_only(&ticket, Status::resolved());
+
+        assert_eq!(edit.status, Status::resolved());
+        assert_eq!(edit.subject, "udf");
+        assert_eq!(edit.requester, ticket.requester);
+        assert_eq!(edit.udf_fields, ticket.udf_fields);
+    }
+
+    #[test]
+    fn udf_flat_handles_flat_and_wrapped_values() {
+        let ticket = ticket_with_udfs(
+            json!({
+                "udf_sline_1": "alert-1",
+                "udf_date_2": { "value": "1700000000000", "display_value": "Nov 14, 2023" },
+                "udf_pick_3": null,
+            }),
+            None,
+        );
+        let flat = ticket.udf_flat();
+        assert_eq!(flat.len(), 3);
+        assert_eq!(flat["udf_sline_1"], json!("alert-1"));
+        assert_eq!(flat["udf_date_2"], json!("1700000000000"));
+        assert_eq!(flat["udf_pick_3"], Value::Null);
+        assert_eq!(
+            ticket.udf_raw()["udf_date_2"]["display_value"],
+            "Nov 14, 2023"
+        );
+    }
+
+    #[test]
+    fn udf_flat_merges_nested_values_and_pipeline_fields() {
+        let ticket = ticket_with_udfs(
+            json!({
+                "udf_sline_1": "Alert One",
+                "values": { "udf_long_2": "42" },
+            }),
+            Some(json!({ "udf_sline_1": "alert-1" })),
+        );
+        let flat = ticket.udf_flat();
+        assert_eq!(flat.len(), 2);
+        assert_eq!(flat["udf_sline_1"], json!("alert-1"));
+        assert_eq!(flat["udf_long_2"], json!("42"));
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct SocUdfs {
+        #[serde(rename = "udf_sline_1")]
+        alert_id: String,
+        #[serde(rename = "udf_pick_3")]
+        severity: Option<String>,
+    }
+
+    #[test]
+    fn udf_struct_deserializes_renamed_fields() {
+        let ticket = ticket_with_udfs(
+            json!({ "udf_sline_1": "alert-1", "udf_pick_3": "High", "udf_other": 5 }),
+            None,
+        );
+
+        let udfs: SocUdfs = ticket.udf_struct().unwrap();
+
+        assert_eq!(
+            udfs,
+            SocUdfs {
+                alert_id: "alert-1".to_string(),
+                severity: Some("High".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn udf_struct_reports_missing_required_field() {
+        let ticket = ticket_with_udfs(Value::Null, None);
+
+        assert!(matches!(
+            ticket.udf_struct::<SocUdfs>(),
+            Err(Error::Serialization(_))
+        ));
+    }
+
+    #[test]
+    fn conversation_summary_counts_attachments() {
+        let resp: ConversationsResponse = serde_json::from_value(json!({
+            "conversations": [
+                {
+                    "id": "1",
+                    "subject": "Re: outage",
+                    "has_attachments": true,
+                    "attachment_count": 3,
+                    "sent_time": { "display_value": "", "value": "1700000000000" },
+                },
+                {
+                    "id": "2",
+                    "has_attachments": true,
+                    "attachments": [{ "id": "a" }, { "id": "b" }],
+                    "created_time": { "display_value": "", "value": "1700000001000" },
+                },
+                { "id": "3" },
+            ],
+        }))
+        .unwrap();
+        let summaries = resp.conversations;
+
+        assert_eq!(summaries[0].attachment_count, 3);
+        assert_eq!(summaries[0].subject.as_deref(), Some("Re: outage"));
+        assert_eq!(summaries[0].time.as_ref().unwrap().value, "1700000000000");
+        assert_eq!(summaries[1].attachment_count, 2);
+        assert_eq!(summaries[1].time.as_ref().unwrap().value, "1700000001000");
+        assert!(!summaries[2].has_attachments);
+        assert_eq!(summaries[2].attachment_count, 0);
+        assert!(summaries[2].time.is_none());
+    }
+
+    #[test]
+    fn dedup_tickets_keeps_first_occurrence_in_order() {
+        let ticket = |id: u64, subject: &str| {
+            let mut ticket = ticket_with_udfs(Value::Null, None);
+            ticket.id = TicketID(id);
+            ticket.subject = subject.to_string();
+            ticket
+        };
+
+        let deduped = dedup_tickets(vec![
+            ticket(3, "first"),
+            ticket(1, "first"),
+            ticket(3, "second"),
+            ticket(2, "first"),
+            ticket(1, "second"),
+        ]);
+
+        let ids: Vec<(u64, &str)> = deduped
+            .iter()
+            .map(|t| (t.id.0, t.subject.as_str()))
+            .collect();
+        assert_eq!(ids, [(3, "first"), (1, "first"), (2, "first")]);
+    }
+
+    #[test]
+    fn site_and_department_deserialize_on_both_responses() {
+        let site = json!({ "id": "5", "name": "HQ" });
+        let department = json!({ "id": "9", "name": "Security", "site": null });
+
+        let detailed: DetailedTicket = serde_json::from_value(json!({
+            "id": "1",
+            "subject": "typed",
+            "status": { "id": "2", "name": "Open", "color": null },
+            "created_by": { "id": "1", "name": "admin" },
+            "created_time": { "display_value": "", "value": "1700000000000" },
+            "site": site,
+            "department": department,
+        }))
+        .unwrap();
+        let created: TicketData = serde_json::from_value(json!({
+            "id": "1",
+            "subject": "typed",
+            "status": { "id": "2", "name": "Open", "color": null },
+            "created_time": { "display_value": "", "value": "1700000000000" },
+            "account": { "id": "301", "name": "SOC" },
+            "template": { "id": "1", "name": "Default" },
+            "site": site,
+            "department": department,
+        }))
+        .unwrap();
+
+        for (site, department) in [
+            (&detailed.site, &detailed.department),
+            (&created.site, &created.department),
+        ] {
+            assert_eq!(site.as_ref().unwrap().name, "HQ");
+            assert_eq!(department.as_ref().unwrap().name, "Security");
+        }
+        assert!(detailed.account.is_none());
+        assert_eq!(created.account.name, "SOC");
+    }
+
+    #[test]
+    fn ticket_data_still_requires_account() {
+        let missing: Result<TicketData, _> = serde_json::from_value(json!({
+            "id": "1",
+            "subject": "typed",
+            "status": { "id": "2", "name": "Open", "color": null },
+            "created_time": { "display_value": "", "value": "1700000000000" },
+            "template": { "id": "1", "name": "Default" },
+        }));
+
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn udf_raw_is_null_without_udfs() {
+        let ticket = ticket_with_udfs(Value::Null, None);
+        assert!(ticket.udf_raw().is_null());
+        assert!(ticket.udf_flat().is_empty());
+    }
+
+    #[test]
+    fn detailed_ticket_account_deserializes_typed() {
+        let mut ticket = json!({
+            "id": "1",
+            "subject": "account",
+            "status": { "id": "2", "name": "Open", "color": null },
+            "created_by": { "id": "1", "name": "admin" },
+            "created_time": { "display_value": "", "value": "1700000000000" },
+            "account": { "id": "301", "name": "SOC", "sub_account": null },
+        });
+        let parsed: DetailedTicket = serde_json::from_value(ticket.clone()).unwrap();
+        assert_eq!(
+            parsed.account,
+            Some(Account {
+                id: "301".to_string(),
+                name: "SOC".to_string(),
+            })
+        );
+
+        ticket["account"] = Value::Null;
+        let parsed: DetailedTicket = serde_json::from_value(ticket).unwrap();
+        assert!(parsed.account.is_none());
+    }
+
+    #[test]
+    fn conversation_attachments_reads_every_content_type() {
+        let attachment = |id: &str| {
+            json!({
+                "id": id,
+                "name": format!("{id}.log"),
+                "content_url": format!("/api/v3/requests/1/attachments/{id}/download"),
+            })
+        };
+        let ids = |content: Value| -> Vec<String> {
+            conversation_attachments(&content)
+                .unwrap()
+                .into_iter()
+                .map(|a| a.id)
+                .collect()
+        };
+
+        assert_eq!(
+            ids(json!({ "notification": { "attachments": [attachment("1")] } })),
+            ["1"]
+        );
+        assert_eq!(
+            ids(json!({ "note": { "attachments": [attachment("2"), attachment("3")] } })),
+            ["2", "3"]
+        );
+        assert_eq!(
+            ids(json!({
+                "note": { "attachments": [attachment("2")] },
+                "request": { "attachments": [attachment("2"), attachment("4")] },
+            })),
+            ["2", "4"]
+        );
+        assert!(ids(json!({ "note": { "attachments": null, "description": "x" } })).is_empty());
     }
 }
@@ -23,6 +23,7 @@ pub(crate) enum SdpErrorCode {
     RateLimitExceeded = 4015,
     AlreadyInTrash = 4016,
     NotInTrash = 4017,
+    RequestLocked = 4018,
     LicenseRestriction = 7001,
     Unknown = 0,
 }
@@ -47,6 +48,7 @@ impl From<u32> for SdpErrorCode {
             4015 => SdpErrorCode::RateLimitExceeded,
             4016 => SdpErrorCode::AlreadyInTrash,
             4017 => SdpErrorCode::NotInTrash,
+            4018 => SdpErrorCode::RequestLocked,
             7001 => SdpErrorCode::LicenseRestriction,
             _ => SdpErrorCode::Unknown,
         }
@@ -56,7 +58,14 @@ impl From<u32> for SdpErrorCode {
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("HTTP error: {0}")]
-    Http(#[from] reqwest::Error),
+    Http(reqwest::Error),
+    #[error("Request timed out: {0}")]
+    Timeout(reqwest::Error),
+    #[error("Connection to SDP failed: {0}")]
+    Connection(reqwest::Error),
+    /// A response body couldn't be decoded, e.g. an HTML page where JSON was expected
+    #[error("Failed to decode response body: {0}")]
+    Decode(#[source] reqwest::Error),
     #[error("Authentication failed: invalid or expired token")]
     Unauthorized,
     #[error("Permission denied: {0}")]
@@ -80,22 +89,40 @@ pub enum Error {
     NotEditable(String),
     #[error("Field does not exist: {0}")]
     NoSuchField(String),
+    /// SDP reported overall success but rejected some fields, e.g. one UDF
+    #[error("Validation failed: {}", join_field_errors(.0))]
+    Validation(Vec<FieldError>),
     #[error("Closure rule violation: {0}")]
     ClosureRuleViolation(String),
     #[error("Rate limit exceeded")]
     RateLimited,
+    /// The request is locked, e.g. while a workflow runs on it; retry later
+    #[error("Request is locked (code {code}): {message}")]
+    Locked { code: u32, message: String },
     #[error("License restriction: operation not allowed")]
     LicenseRestricted,
+    /// The instance doesn't offer the feature, e.g. tags are turned off
+    #[error("Feature disabled: {0}")]
+    FeatureDisabled(String),
     #[error("SDP internal error")]
     Internal,
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
     #[error("URL parsing error: {0}")]
     UrlParse(#[from] url::ParseError),
-    #[error("Form encoding error: {0}")]
-    FormEncoding(#[from] serde_urlencoded::ser::Error),
+    /// Encoding a request body failed; `context` names the request being built
+    #[error("Form encoding error while {context}: {source}")]
+    FormEncoding {
+        context: String,
+        #[source]
+        source: serde_urlencoded::ser::Error,
+    },
     #[error("SDP error (code {code}): {message}")]
     Sdp { code: u32, message: String },
+    /// Error response whose body isn't an SDP status object, e.g. a proxy error page.
+    /// `body` is empty when the body wasn't read.
+    #[error("Unexpected response (HTTP {status}): {body}")]
+    UnexpectedResponse { status: u16, body: String },
     #[error("Required field missing: {0}")]
     FieldRequired(String),
     #[error("{0}")]
@@ -104,6 +131,53 @@ pub enum Error {
     Io(#[from] std::io::Error),
 }
 
+/// A single field SDP refused while accepting the rest of the request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    /// Name of the rejected field, when SDP reports it
+    pub field: Option<String>,
+    pub code: u32,
+    pub message: String,
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.field {
+            Some(field) => write!(f, "{field} (code {}): {}", self.code, self.message),
+            None => write!(f, "code {}: {}", self.code, self.message),
+        }
+    }
+}
+
+fn join_field_errors(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            Error::Timeout(error)
+        } else if error.is_connect() {
+            Error::Connection(error)
+        } else if error.is_decode() {
+            Error::Decode(error)
+        } else if error.is_status()
+            && let Some(status) = error.status()
+        {
+            Error::UnexpectedResponse {
+                status: status.as_u16(),
+                body: String::new(),
+            }
+        } else {
+            Error::Http(error)
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
 #[serde(untagged)]
 pub(crate) enum SdpErrorMessage {
@@ -115,8 +189,14 @@ pub(crate) enum SdpErrorMessage {
     Other(serde_json::Value),
 }
 
+impl Default for SdpErrorMessage {
+    fn default() -> Self {
+        Self::Text(String::new())
+    }
+}
+
 impl SdpErrorMessage {
-    fn into_text(self) -> String {
+    pub(crate) fn into_text(self) -> String {
         match self {
             Self::Merged { message, .. } | Self::Text(message) => message,
             Self::Other(value) => value.to_string(),
@@ -166,13 +246,63 @@ impl Error {
             SdpErrorCode::NoSuchField => Error::NoSuchField(field_info),
             SdpErrorCode::MissingMandatoryField => Error::MissingField(field_info),
             SdpErrorCode::RateLimitExceeded => Error::RateLimited,
+            SdpErrorCode::RequestLocked => Error::Locked { code, message },
             SdpErrorCode::LicenseRestriction => Error::LicenseRestricted,
             SdpErrorCode::AlreadyInTrash | SdpErrorCode::NotInTrash => Error::Sdp { code, message },
             SdpErrorCode::UnsupportedContentType => Error::Sdp { code, message },
             SdpErrorCode::Success => Error::Other("Unexpected success code in error path".into()),
+            // Some builds report a lock with the generic 4000 code, recognise it by its message.
+            SdpErrorCode::Unknown
+                if code == 4000 && message.to_ascii_lowercase().contains("locked") =>
+            {
+                Error::Locked { code, message }
+            }
             SdpErrorCode::Unknown => Error::Sdp { code, message },
         }
     }
+
+    /// HTTP status code best describing this error, for integrations that
+    /// re-expose SDP through their own API.
+    ///
+    /// Client-side mistakes map to 4xx, conflicts with the ticket's current
+    /// state to 409, and failures talking to SDP to 502/504. Errors raised
+    /// while building a request locally, and SDP internal errors, map to 500.
+    pub fn http_status(&self) -> u16 {
+        match self {
+            Error::Http(e) => e.status().map_or(502, |status| status.as_u16()),
+            Error::Timeout(_) => 504,
+            Error::Connection(_) | Error::Decode(_) => 502,
+            Error::Unauthorized => 401,
+            Error::Forbidden(_) | Error::LicenseRestricted => 403,
+            Error::NotFound(_) => 404,
+            Error::InvalidValue(_)
+            | Error::MissingField(_)
+            | Error::NotEditable(_)
+            | Error::NoSuchField(_)
+            | Error::Validation(_)
+            | Error::FieldRequired(_) => 400,
+            Error::RequestMerged { .. }
+            | Error::NotUnique(_)
+            | Error::ReferenceExists
+            | Error::ClosureRuleViolation(_) => 409,
+            Error::RateLimited => 429,
+            Error::Locked { .. } => 423,
+            Error::FeatureDisabled(_) => 501,
+            Error::Internal => 500,
+            Error::Sdp { code, .. } => match SdpErrorCode::from(*code) {
+                SdpErrorCode::AlreadyInTrash | SdpErrorCode::NotInTrash => 409,
+                SdpErrorCode::UnsupportedContentType => 415,
+                _ => 502,
+            },
+            Error::UnexpectedResponse { status, .. } if (400..=599).contains(status) => *status,
+            Error::UnexpectedResponse { .. } => 502,
+            Error::Serialization(_)
+            | Error::UrlParse(_)
+            | Error::FormEncoding { .. }
+            | Error::Other(_)
+            | Error::Io(_) => 500,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -204,6 +334,7 @@ mod tests {
         assert_eq!(SdpErrorCode::from(4015), SdpErrorCode::RateLimitExceeded);
         assert_eq!(SdpErrorCode::from(4016), SdpErrorCode::AlreadyInTrash);
         assert_eq!(SdpErrorCode::from(4017), SdpErrorCode::NotInTrash);
+        assert_eq!(SdpErrorCode::from(4018), SdpErrorCode::RequestLocked);
         assert_eq!(SdpErrorCode::from(7001), SdpErrorCode::LicenseRestriction);
         assert_eq!(SdpErrorCode::from(9999), SdpErrorCode::Unknown);
         assert_eq!(SdpErrorCode::from(0), SdpErrorCode::Unknown);
@@ -275,6 +406,81 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn reqwest_timeout_maps_to_timeout() {
+        // Accept the connection but never answer it.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (_socket, _) = listener.accept().await.unwrap();
+            std::future::pending::<()>().await;
+        });
+
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let err = client
+            .get(format!("http://{addr}/"))
+            .send()
+            .await
+            .unwrap_err();
+
+        assert!(matches!(Error::from(err), Error::Timeout(_)));
+    }
+
+    #[tokio::test]
+    async fn reqwest_connect_error_maps_to_connection() {
+        // Grab a free port and close it again so nothing is listening there.
+        let addr = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap()
+        };
+
+        let err = reqwest::get(format!("http://{addr}/")).await.unwrap_err();
+
+        assert!(matches!(Error::from(err), Error::Connection(_)));
+    }
+
+    #[tokio::test]
+    async fn reqwest_status_and_decode_errors_are_classified() {
+        use wiremock::{Mock, MockServer, ResponseTemplate, matchers::path};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/missing"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(path("/html"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html>"))
+            .mount(&server)
+            .await;
+
+        let status_err = reqwest::get(format!("{}/missing", server.uri()))
+            .await
+            .unwrap()
+            .error_for_status()
+            .unwrap_err();
+        assert!(matches!(
+            Error::from(status_err),
+            Error::UnexpectedResponse { status: 404, .. }
+        ));
+
+        let decode_err = reqwest::get(format!("{}/html", server.uri()))
+            .await
+            .unwrap()
+            .json::<serde_json::Value>()
+            .await
+            .unwrap_err();
+        assert!(matches!(Error::from(decode_err), Error::Decode(_)));
+
+        let builder_err = reqwest::Client::new()
+            .get("http://[::1")
+            .build()
+            .unwrap_err();
+        assert!(matches!(Error::from(builder_err), Error::Http(_)));
+    }
+
     #[test]
     fn structured_merged_response_maps_to_request_merged() {
         let response: SdpErrorMessage = serde_json::from_value(serde_json::json!({
@@ -293,4 +499,124 @@ mod tests {
             } if message.contains("583550")
         ));
     }
+
+    #[test]
+    fn http_status_per_variant() {
+        let builder_error = || {
+            reqwest::Client::new()
+                .get("http://[::1")
+                .build()
+                .unwrap_err()
+        };
+        let cases = [
+            (Error::Http(builder_error()), 502),
+            (Error::Timeout(builder_error()), 504),
+            (Error::Connection(builder_error()), 502),
+            (Error::Decode(builder_error()), 502),
+            (Error::Unauthorized, 401),
+            (Error::Forbidden("x".into()), 403),
+            (Error::LicenseRestricted, 403),
+            (Error::NotFound("x".into()), 404),
+            (Error::InvalidValue("x".into()), 400),
+            (Error::MissingField("x".into()), 400),
+            (Error::NotEditable("x".into()), 400),
+            (Error::NoSuchField("x".into()), 400),
+            (
+                Error::Validation(vec![FieldError {
+                    field: Some("udf_sline_1".into()),
+                    code: 4001,
+                    message: "x".into(),
+                }]),
+                400,
+            ),
+            (Error::FieldRequired("x".into()), 400),
+            (
+                Error::RequestMerged {
+                    parent_request_id: TicketID(1),
+                    message: "x".into(),
+                },
+                409,
+            ),
+            (Error::NotUnique("x".into()), 409),
+            (Error::ReferenceExists, 409),
+            (Error::ClosureRuleViolation("x".into()), 409),
+            (Error::RateLimited, 429),
+            (
+                Error::Locked {
+                    code: 4018,
+                    message: "x".into(),
+                },
+                423,
+            ),
+            (Error::FeatureDisabled("x".into()), 501),
+            (Error::Internal, 500),
+            (
+                Error::Sdp {
+                    code: 4017,
+                    message: "x".into(),
+                },
+                409,
+            ),
+            (
+                Error::Sdp {
+                    code: 4013,
+                    message: "x".into(),
+                },
+                415,
+            ),
+            (
+                Error::Sdp {
+                    code: 4999,
+                    message: "x".into(),
+                },
+                502,
+            ),
+            (
+                Error::UnexpectedResponse {
+                    status: 503,
+                    body: String::new(),
+                },
+                503,
+            ),
+            (
+                Error::UnexpectedResponse {
+                    status: 302,
+                    body: String::new(),
+                },
+                502,
+            ),
+            (
+                Error::Serialization(serde_json::from_str::<u8>("x").unwrap_err()),
+                500,
+            ),
+            (Error::UrlParse(url::ParseError::EmptyHost), 500),
+            (
+                Error::FormEncoding {
+                    context: "x".into(),
+                    source: serde_urlencoded::ser::Error::Custom("x".into()),
+                },
+                500,
+            ),
+            (Error::Other("x".into()), 500),
+            (Error::Io(std::io::Error::other("x")), 500),
+        ];
+
+        for (error, expected) in cases {
+            assert_eq!(error.http_status(), expected, "{error:?}");
+        }
+    }
+
+    #[test]
+    fn locked_message_with_generic_code_maps_to_locked() {
+        let error = Error::from_sdp(4000, "Request is locked by a workflow".into(), None);
+
+        assert!(matches!(error, Error::Locked { code: 4000, .. }));
+    }
+
+    #[test]
+    fn locked_message_with_other_code_is_not_locked() {
+        let error = Error::from_sdp(4042, "User account locked".into(), None);
+
+        assert!(matches!(error, Error::Sdp { code: 4042, .. }));
+    }
 }
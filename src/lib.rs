@@ -33,30 +33,53 @@
 //!
 //! See [`ServiceDesk`] for the main entry point.
 
+use std::sync::Arc;
 use std::time::Duration;
 
+use chrono::{DateTime, Utc};
+
 use reqwest::{
     Url,
     header::{HeaderMap, HeaderName, HeaderValue},
 };
 use serde::{Deserialize, Serialize};
 
+use crate::auth::Auth;
+
+mod announcement;
+mod approval;
 mod auth;
 mod builders;
+mod change;
 mod client;
 mod error;
-
-pub use crate::auth::Credentials;
+mod problem;
+mod reminder;
+mod retry;
+mod tag;
+
+pub use crate::auth::{Credentials, TokenLocation};
+pub use announcement::{Announcement, AnnouncementData};
+pub use approval::{Approval, ApprovalStatus};
 pub use builders::{
-    NoteBuilder, TicketClient, TicketCreateBuilder, TicketSearchBuilder, TicketStatus,
-    TicketsClient, WorklogBuilder,
+    CloseBuilder, ClosureCodeKind, EmptyTrashBuilder, NoteBuilder, NotesPager, ReplyBuilder,
+    TicketClient, TicketCreateBuilder, TicketSearchBuilder, TicketStatus, TicketsClient,
+    WorklogBuilder,
 };
+pub use change::{AssociatedRequest, Change, ChangeData};
 pub use client::{
-    Account, Attachment, Condition, CreateTicketData, Criteria, DetailedTicket, EditTicketData,
-    LogicalOp, Note, NoteData, Priority, Resolution, Status, TemplateInfo, TicketData, TimeEntry,
-    UserInfo,
+    Account, AssociatedItem, Association, Attachment, AttachmentMeta, BATCH_CONCURRENCY,
+    ClosureCode, Condition, Conversation, ConversationSummary, CreateTicketData, Criteria,
+    Department, DetailedTicket, EditTicketData, ListInfoResponse, LogicalOp, MergeResult, Note,
+    NoteData, OperationResult, Priority, ReplyData, Resolution, SearchPage, ServerInfo, Site, Sla,
+    SlaEscalation, Status, Template, TemplateInfo, ThreadItem, TicketData, TimeEntry,
+    TrashedTicket, UdfFieldDefinition, UdfFieldType, UserInfo, dedup_tickets, encode_input_data,
 };
-pub use error::Error;
+pub use error::{Error, FieldError};
+pub use problem::{Problem, ProblemData};
+pub use reminder::Reminder;
+pub use retry::RetryPolicy;
+pub use tag::Tag;
 
 /// Type-safe wrapper for User ID in SDP
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq, Hash, Default)]
@@ -99,6 +122,25 @@ impl<'de> serde::de::Visitor<'de> for StringOrNumberU64Visitor {
     }
 }
 
+/// Deserialize a `u64` sent either as a number or as a numeric string.
+pub(crate) fn deserialize_string_or_u64<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<u64, D::Error> {
+    deserializer.deserialize_any(StringOrNumberU64Visitor)
+}
+
+/// Deserialize an SDP status code sent either as a number (`2000`) or as a
+/// numeric string (`"2000"`).
+pub(crate) fn deserialize_status_code<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: TryFrom<u64>,
+{
+    let code = deserializer.deserialize_any(StringOrNumberU64Visitor)?;
+    T::try_from(code)
+        .map_err(|_| serde::de::Error::custom(format!("status code out of range: {code}")))
+}
+
 impl<'de> Deserialize<'de> for TicketID {
     fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
         deserializer
@@ -139,18 +181,68 @@ impl From<u64> for TicketID {
     }
 }
 
+impl From<&u64> for TicketID {
+    fn from(value: &u64) -> Self {
+        TicketID(*value)
+    }
+}
+
+impl From<&TicketID> for TicketID {
+    fn from(value: &TicketID) -> Self {
+        *value
+    }
+}
+
 impl From<TicketID> for u64 {
     fn from(value: TicketID) -> Self {
         value.0
     }
 }
 
+/// Parses a numeric ticket id such as `"12345"`.
+///
+/// There is deliberately no infallible `From<&str>`, non-numeric input returns
+/// [`Error::InvalidValue`] instead of panicking.
+impl std::str::FromStr for TicketID {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim()
+            .parse::<u64>()
+            .map(TicketID)
+            .map_err(|_| Error::InvalidValue(format!("invalid ticket id: {s:?}")))
+    }
+}
+
+impl TryFrom<&str> for TicketID {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl TryFrom<String> for TicketID {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
 impl From<&TicketID> for u64 {
     fn from(value: &TicketID) -> Self {
         value.0
     }
 }
 
+impl UserID {
+    /// The id as a number, `None` if SDP returned a non-numeric id.
+    pub fn as_u64(&self) -> Option<u64> {
+        self.0.parse().ok()
+    }
+}
+
 impl From<&UserID> for String {
     fn from(value: &UserID) -> Self {
         value.0.clone()
@@ -175,6 +267,10 @@ impl From<u32> for UserID {
     }
 }
 
+/// Deprecated: non-numeric or out of range ids silently become `0`, use
+/// [`UserID::as_u64`] instead.
+///
+/// Kept for compatibility, Rust can't attach `#[deprecated]` to trait impls.
 impl From<UserID> for u32 {
     fn from(value: UserID) -> Self {
         value.0.parse().unwrap_or_default()
@@ -201,25 +297,120 @@ impl std::fmt::Display for UserID {
 pub struct ServiceDesk {
     base_url: Url,
     inner: reqwest::Client,
+    auth: Auth,
+    max_subject_length: Option<usize>,
+    input_data_mode: Option<InputDataMode>,
+    retry: Option<RetryPolicy>,
+    clock: Clock,
 }
 
+/// Source of the current time for relative search filters.
+type Clock = Arc<dyn Fn() -> DateTime<Utc> + Send + Sync>;
+
 /// Security options for the ServiceDesk client
-///
-/// Not finished yet!!
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug)]
 pub enum Security {
+    /// Accept any server certificate, including self-signed and expired ones.
+    ///
+    /// Disables all certificate validation, only opt into this for testing.
+    /// Only accepted by [`ServiceDesk::new_insecure`], [`ServiceDesk::new`] rejects it.
     Unsafe,
+    /// Validate server certificates against the system roots (default).
     NativeTLS,
+    /// Present a client certificate for mutual TLS.
+    ///
+    /// Uses reqwest's default rustls backend, so the identity must be built with
+    /// [`reqwest::Identity::from_pem`] (certificate chain plus PKCS#8, RSA or SEC1 key).
+    ClientCert { identity: reqwest::Identity },
+}
+
+/// TLS and connection settings, applied together through [`ServiceDeskOptions::tls`].
+///
+/// Replaces [`ServiceDeskOptions::security`] and [`ServiceDeskOptions::root_ca`],
+/// combining it with either is rejected by [`ServiceDesk::new`].
+#[derive(Clone, Debug, Default)]
+pub struct TlsConfig {
+    /// Accept any server certificate, see [`Security::Unsafe`]. Can't be combined
+    /// with `client_identity` or `root_ca`, and needs [`ServiceDesk::new_insecure`]
+    pub accept_invalid_certs: bool,
+    /// Client certificate for mutual TLS, see [`Security::ClientCert`]
+    pub client_identity: Option<reqwest::Identity>,
+    /// Extra root certificate to trust, e.g. an internal CA
+    pub root_ca: Option<reqwest::Certificate>,
+    /// Timeout for establishing the connection, including the TLS handshake
+    pub connect_timeout: Option<Duration>,
+}
+
+impl TlsConfig {
+    fn validate(&self) -> Result<(), Error> {
+        if self.accept_invalid_certs && self.client_identity.is_some() {
+            return Err(Error::InvalidValue(
+                "tls: accept_invalid_certs can't be combined with a client identity".into(),
+            ));
+        }
+        if self.accept_invalid_certs && self.root_ca.is_some() {
+            return Err(Error::InvalidValue(
+                "tls: accept_invalid_certs can't be combined with a root CA".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn apply(self, mut builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        builder = builder.danger_accept_invalid_certs(self.accept_invalid_certs);
+        if let Some(identity) = self.client_identity {
+            builder = builder.identity(identity);
+        }
+        if let Some(root_ca) = self.root_ca {
+            builder = builder.add_root_certificate(root_ca);
+        }
+        if let Some(connect_timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        builder
+    }
+}
+
+/// Where the JSON `input_data` parameter is placed on a request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputDataMode {
+    /// URL query string, what most SDP operations expect.
+    Query,
+    /// `application/x-www-form-urlencoded` request body.
+    Form,
 }
 
 /// Configuration options for the ServiceDesk client
 #[derive(Clone, Debug)]
 pub struct ServiceDeskOptions {
     pub user_agent: Option<String>,
-    /// Request timeout duration
+    /// Appended to [`user_agent`](Self::user_agent) after a space, e.g. `my-app/1.2`,
+    /// so server logs can attribute calls while keeping the crate's identifier
+    pub user_agent_suffix: Option<String>,
+    /// Request timeout duration, `None` disables the timeout entirely
     pub timeout: Option<Duration>,
     pub security: Option<Security>,
     pub default_headers: Option<HeaderMap>,
+    /// How the auth token is attached to requests
+    pub token_location: TokenLocation,
+    /// Extra root certificate to trust, e.g. an internal CA.
+    ///
+    /// Added on top of the built-in roots, a safer alternative to [`Security::Unsafe`].
+    pub root_ca: Option<reqwest::Certificate>,
+    /// Longest subject accepted when creating or editing tickets, checked before
+    /// sending. `None` disables the check
+    pub max_subject_length: Option<usize>,
+    /// Force one placement of `input_data` for every request.
+    ///
+    /// `None` lets each operation pick: the query string for most, the form body
+    /// for merges. Some instances only accept form-encoded GETs, use
+    /// `Some(InputDataMode::Form)` for those.
+    pub input_data_mode: Option<InputDataMode>,
+    /// Retry rate-limited and transiently failing requests, `None` sends each
+    /// request once. Multipart uploads are never retried.
+    pub retry: Option<RetryPolicy>,
+    /// Grouped TLS settings, set through [`tls`](Self::tls).
+    pub tls: Option<TlsConfig>,
 }
 
 static SDP_HEADER: (HeaderName, HeaderValue) = (
@@ -231,52 +422,174 @@ impl Default for ServiceDeskOptions {
     fn default() -> Self {
         ServiceDeskOptions {
             user_agent: Some(String::from("servicedesk-rs/0.1.0")),
+            user_agent_suffix: None,
             timeout: Some(Duration::from_secs(5)),
-            security: Some(Security::Unsafe),
+            security: Some(Security::NativeTLS),
             default_headers: Some(HeaderMap::from_iter(vec![SDP_HEADER.clone()])),
+            token_location: TokenLocation::default(),
+            root_ca: None,
+            max_subject_length: Some(250),
+            input_data_mode: None,
+            retry: None,
+            tls: None,
         }
     }
 }
 
+impl ServiceDeskOptions {
+    /// Use `tls` for all TLS settings, clearing [`security`](Self::security)
+    /// and [`root_ca`](Self::root_ca).
+    pub fn tls(mut self, tls: TlsConfig) -> Self {
+        self.security = None;
+        self.root_ca = None;
+        self.tls = Some(tls);
+        self
+    }
+}
+
+fn compose_user_agent(user_agent: Option<String>, suffix: Option<String>) -> String {
+    match (user_agent.filter(|ua| !ua.is_empty()), suffix) {
+        (Some(ua), Some(suffix)) => format!("{ua} {suffix}"),
+        (ua, suffix) => ua.or(suffix).unwrap_or_default(),
+    }
+}
+
 impl ServiceDesk {
     /// Create a new ServiceDesk client instance.
     ///
+    /// Server certificates are always validated, see
+    /// [`new_insecure`](Self::new_insecure) for the opposite.
+    ///
     /// # Errors
     ///
-    /// Returns an error if the auth token contains invalid header characters
-    /// or if the underlying HTTP client fails to build.
+    /// Returns an error if the auth token (or custom auth header name) contains
+    /// invalid header characters or if the underlying HTTP client fails to build.
+    /// Options asking to accept invalid certificates return [`Error::InvalidValue`].
     pub fn new(
         base_url: Url,
         credentials: Credentials,
         options: ServiceDeskOptions,
     ) -> Result<Self, Error> {
-        let mut headers = options.default_headers.unwrap_or_default();
+        Self::build(base_url, credentials, options, false)
+    }
 
-        if let Credentials::Token { ref token } = credentials {
-            let value = HeaderValue::from_str(token)
-                .map_err(|e| Error::Other(format!("invalid auth token header value: {e}")))?;
-            headers.insert("authtoken", value);
+    /// Create a client that accepts any server certificate, including
+    /// self-signed and expired ones.
+    ///
+    /// Certificate validation is disabled entirely, only use this for testing.
+    /// Otherwise behaves like [`new`](Self::new), except that options with a
+    /// root CA or a client identity are rejected with [`Error::InvalidValue`],
+    /// as accepting any certificate would silently override them.
+    pub fn new_insecure(
+        base_url: Url,
+        credentials: Credentials,
+        options: ServiceDeskOptions,
+    ) -> Result<Self, Error> {
+        Self::build(base_url, credentials, options, true)
+    }
+
+    fn build(
+        base_url: Url,
+        credentials: Credentials,
+        options: ServiceDeskOptions,
+        insecure: bool,
+    ) -> Result<Self, Error> {
+        let wants_insecure = matches!(options.security, Some(Security::Unsafe))
+            || options
+                .tls
+                .as_ref()
+                .is_some_and(|tls| tls.accept_invalid_certs);
+        if let Some(tls) = &options.tls {
+            tls.validate()?;
+            // The default `NativeTLS` is what `TlsConfig` starts from, anything else conflicts.
+            let conflicting_security =
+                !matches!(options.security, None | Some(Security::NativeTLS));
+            if conflicting_security || options.root_ca.is_some() {
+                return Err(Error::InvalidValue(
+                    "tls can't be combined with the security or root_ca options".into(),
+                ));
+            }
+        }
+        let pins_certificates = options.root_ca.is_some()
+            || matches!(options.security, Some(Security::ClientCert { .. }))
+            || options
+                .tls
+                .as_ref()
+                .is_some_and(|tls| tls.root_ca.is_some() || tls.client_identity.is_some());
+        if insecure && pins_certificates {
+            return Err(Error::InvalidValue(
+                "new_insecure can't be combined with a root CA or client identity".into(),
+            ));
         }
+        if wants_insecure && !insecure {
+            return Err(Error::InvalidValue(
+                "accepting invalid certificates requires ServiceDesk::new_insecure".into(),
+            ));
+        }
+
+        let auth = Auth::new(&credentials, &options.token_location)?;
 
         let mut builder = reqwest::ClientBuilder::new()
-            .default_headers(headers)
-            .user_agent(options.user_agent.unwrap_or_default())
-            .timeout(options.timeout.unwrap_or_else(|| Duration::from_secs(5)));
+            .default_headers(options.default_headers.unwrap_or_default())
+            .user_agent(compose_user_agent(
+                options.user_agent,
+                options.user_agent_suffix,
+            ));
+
+        if let Some(timeout) = options.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        if let Some(tls) = options.tls {
+            builder = tls.apply(builder);
+        }
+
+        if let Some(root_ca) = options.root_ca {
+            builder = builder.add_root_certificate(root_ca);
+        }
 
         if let Some(security) = options.security {
             match security {
                 Security::Unsafe => {
                     builder = builder.danger_accept_invalid_certs(true);
                 }
-                Security::NativeTLS => {}
+                Security::NativeTLS => {
+                    builder = builder.danger_accept_invalid_certs(false);
+                }
+                Security::ClientCert { identity } => {
+                    builder = builder.identity(identity);
+                }
             }
         }
 
+        if insecure {
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
         let inner = builder
             .build()
             .map_err(|e| Error::Other(format!("failed to build HTTP client: {e}")))?;
 
-        Ok(ServiceDesk { base_url, inner })
+        Ok(ServiceDesk {
+            base_url,
+            inner,
+            auth,
+            max_subject_length: options.max_subject_length,
+            input_data_mode: options.input_data_mode,
+            retry: options.retry,
+            clock: Arc::new(Utc::now),
+        })
+    }
+
+    /// Replace the clock used by relative time filters such as
+    /// [`TicketSearchBuilder::created_in_last`], mainly to freeze time in tests.
+    pub fn with_clock(mut self, clock: impl Fn() -> DateTime<Utc> + Send + Sync + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    pub(crate) fn now(&self) -> DateTime<Utc> {
+        (self.clock)()
     }
 }
 
@@ -288,8 +601,178 @@ mod tests {
     fn service_desk_options_default() {
         let opts = ServiceDeskOptions::default();
         assert_eq!(opts.user_agent, Some("servicedesk-rs/0.1.0".to_string()));
+        assert_eq!(opts.user_agent_suffix, None);
+        assert_eq!(opts.retry, None);
         assert_eq!(opts.timeout, Some(Duration::from_secs(5)));
-        assert!(matches!(opts.security, Some(Security::Unsafe)));
+        assert!(matches!(opts.security, Some(Security::NativeTLS)));
         assert!(opts.default_headers.is_some());
+        assert_eq!(opts.token_location, TokenLocation::AuthTokenHeader);
+        assert!(opts.root_ca.is_none());
+        assert_eq!(opts.max_subject_length, Some(250));
+        assert!(opts.tls.is_none());
+    }
+
+    fn client_with_options(options: ServiceDeskOptions) -> Result<ServiceDesk, Error> {
+        ServiceDesk::new(
+            Url::parse("https://sdp.example.com").unwrap(),
+            Credentials::Token {
+                token: "token".into(),
+            },
+            options,
+        )
+    }
+
+    fn insecure_client_with_options(options: ServiceDeskOptions) -> Result<ServiceDesk, Error> {
+        ServiceDesk::new_insecure(
+            Url::parse("https://sdp.example.com").unwrap(),
+            Credentials::Token {
+                token: "token".into(),
+            },
+            options,
+        )
+    }
+
+    #[test]
+    fn tls_config_builds_client() {
+        let pem = include_bytes!("../tests/fixtures/client-identity.pem");
+        let options = ServiceDeskOptions::default().tls(TlsConfig {
+            client_identity: Some(reqwest::Identity::from_pem(pem).unwrap()),
+            root_ca: Some(reqwest::Certificate::from_pem(pem).unwrap()),
+            connect_timeout: Some(Duration::from_secs(2)),
+            ..Default::default()
+        });
+
+        assert!(options.security.is_none());
+        assert!(client_with_options(options).is_ok());
+    }
+
+    #[test]
+    fn tls_config_rejects_conflicting_settings() {
+        let pem = include_bytes!("../tests/fixtures/client-identity.pem");
+        let unsafe_with_identity = ServiceDeskOptions::default().tls(TlsConfig {
+            accept_invalid_certs: true,
+            client_identity: Some(reqwest::Identity::from_pem(pem).unwrap()),
+            ..Default::default()
+        });
+        assert!(matches!(
+            client_with_options(unsafe_with_identity),
+            Err(Error::InvalidValue(_))
+        ));
+
+        let unsafe_with_root_ca = ServiceDeskOptions::default().tls(TlsConfig {
+            accept_invalid_certs: true,
+            root_ca: Some(reqwest::Certificate::from_pem(pem).unwrap()),
+            ..Default::default()
+        });
+        assert!(matches!(
+            client_with_options(unsafe_with_root_ca),
+            Err(Error::InvalidValue(_))
+        ));
+
+        let tls_with_security = ServiceDeskOptions {
+            security: Some(Security::Unsafe),
+            tls: Some(TlsConfig::default()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            insecure_client_with_options(tls_with_security),
+            Err(Error::InvalidValue(msg)) if msg.contains("tls can't be combined")
+        ));
+    }
+
+    #[test]
+    fn new_insecure_rejects_pinned_certificates() {
+        let pem = include_bytes!("../tests/fixtures/client-identity.pem");
+        let with_root_ca = ServiceDeskOptions {
+            root_ca: Some(reqwest::Certificate::from_pem(pem).unwrap()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            insecure_client_with_options(with_root_ca),
+            Err(Error::InvalidValue(_))
+        ));
+
+        let with_identity = ServiceDeskOptions::default().tls(TlsConfig {
+            client_identity: Some(reqwest::Identity::from_pem(pem).unwrap()),
+            ..Default::default()
+        });
+        assert!(matches!(
+            insecure_client_with_options(with_identity),
+            Err(Error::InvalidValue(_))
+        ));
+        assert!(insecure_client_with_options(ServiceDeskOptions::default()).is_ok());
+    }
+
+    #[test]
+    fn user_id_as_u64() {
+        assert_eq!(UserID::from("42").as_u64(), Some(42));
+        assert_eq!(UserID::from(7u32).as_u64(), Some(7));
+        assert_eq!(UserID::from("tech-a1b2").as_u64(), None);
+        assert_eq!(UserID::default().as_u64(), None);
+    }
+
+    #[test]
+    fn ticket_id_from_numeric_string() {
+        assert_eq!(TicketID::try_from("12345").unwrap(), TicketID(12345));
+        assert_eq!(
+            TicketID::try_from(" 42 ".to_string()).unwrap(),
+            TicketID(42)
+        );
+        assert_eq!("7".parse::<TicketID>().unwrap(), TicketID(7));
+    }
+
+    #[test]
+    fn ticket_id_from_invalid_string() {
+        assert!(matches!(
+            TicketID::try_from("abc"),
+            Err(Error::InvalidValue(_))
+        ));
+        assert!(matches!(
+            TicketID::try_from(""),
+            Err(Error::InvalidValue(_))
+        ));
+        assert!(matches!(
+            TicketID::try_from("-1".to_string()),
+            Err(Error::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn client_cert_identity_builds_client() {
+        let pem = include_bytes!("../tests/fixtures/client-identity.pem");
+        let identity = reqwest::Identity::from_pem(pem).unwrap();
+
+        let client = ServiceDesk::new(
+            Url::parse("https://sdp.example.com").unwrap(),
+            Credentials::Token {
+                token: "token".into(),
+            },
+            ServiceDeskOptions {
+                security: Some(Security::ClientCert { identity }),
+                ..Default::default()
+            },
+        );
+
+        assert!(client.is_ok());
+    }
+
+    #[test]
+    fn root_ca_builds_client() {
+        let pem = include_bytes!("../tests/fixtures/client-identity.pem");
+        let root_ca = reqwest::Certificate::from_pem(pem).unwrap();
+
+        let client = ServiceDesk::new(
+            Url::parse("https://sdp.example.com").unwrap(),
+            Credentials::Token {
+                token: "token".into(),
+            },
+            ServiceDeskOptions {
+                security: Some(Security::NativeTLS),
+                root_ca: Some(root_ca),
+                ..Default::default()
+            },
+        );
+
+        assert!(client.is_ok());
     }
 }
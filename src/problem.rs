@@ -0,0 +1,89 @@
+//! Problems module (`/api/v3/problems`).
+
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    ServiceDesk,
+    client::{
+        Priority, Status, TimeEntry, UserInfo, deserialize_optional_user_info,
+        serialize_optional_name_object,
+    },
+    error::Error,
+};
+
+/// A problem as returned by SDP.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Problem {
+    pub id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: Option<Status>,
+    pub priority: Option<Priority>,
+    pub technician: Option<UserInfo>,
+    #[serde(default, deserialize_with = "deserialize_optional_user_info")]
+    pub requester: Option<UserInfo>,
+    pub created_time: Option<TimeEntry>,
+    pub due_by_time: Option<TimeEntry>,
+    pub closed_time: Option<TimeEntry>,
+    pub udf_fields: Option<Value>,
+}
+
+/// Data for creating a problem. Unset optional fields are omitted.
+///
+/// `status` and `technician` are sent by name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ProblemData {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_name_object"
+    )]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_name_object"
+    )]
+    pub technician: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub udf_fields: Option<Value>,
+}
+
+#[derive(Serialize, Debug)]
+struct CreateProblemRequest<'a> {
+    problem: &'a ProblemData,
+}
+
+#[derive(Deserialize, Debug)]
+struct ProblemResponse {
+    problem: Problem,
+}
+
+impl ServiceDesk {
+    /// Get a single problem.
+    pub async fn get_problem(&self, id: &str) -> Result<Problem, Error> {
+        tracing::info!(problem_id = %id, "fetching problem");
+        let resp: ProblemResponse = self
+            .request_with_path(Method::GET, &format!("/api/v3/problems/{}", id))
+            .await?;
+        Ok(resp.problem)
+    }
+
+    /// Create a new problem.
+    pub async fn create_problem(&self, data: &ProblemData) -> Result<Problem, Error> {
+        tracing::info!(title = %data.title, "creating problem");
+        let resp: ProblemResponse = self
+            .request_input_data(
+                Method::POST,
+                "/api/v3/problems",
+                &CreateProblemRequest { problem: data },
+            )
+            .await?;
+        Ok(resp.problem)
+    }
+}
@@ -0,0 +1,135 @@
+//! Request reminders (`/api/v3/requests/{id}/reminders`).
+
+use chrono::{DateTime, Utc};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ServiceDesk, TicketID,
+    builders::serialize_sdp_time,
+    client::{TimeEntry, UserInfo},
+    error::Error,
+};
+
+/// A reminder set on a request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reminder {
+    pub id: String,
+    #[serde(default)]
+    pub message: String,
+    pub remind_at: Option<TimeEntry>,
+    pub created_by: Option<UserInfo>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReminderData<'a> {
+    message: &'a str,
+    #[serde(serialize_with = "serialize_sdp_time")]
+    remind_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Debug)]
+struct AddReminderRequest<'a> {
+    reminder: ReminderData<'a>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ReminderResponse {
+    reminder: Reminder,
+}
+
+#[derive(Deserialize, Debug)]
+struct RemindersListResponse {
+    #[serde(default)]
+    reminders: Vec<Reminder>,
+}
+
+impl ServiceDesk {
+    /// Set a reminder on a request.
+    pub async fn add_reminder(
+        &self,
+        ticket_id: impl Into<TicketID>,
+        message: &str,
+        remind_at: DateTime<Utc>,
+    ) -> Result<Reminder, Error> {
+        let ticket_id = ticket_id.into();
+        tracing::info!(ticket_id = %ticket_id, %remind_at, "adding reminder");
+        let resp: ReminderResponse = self
+            .request_input_data(
+                Method::POST,
+                &format!("/api/v3/requests/{}/reminders", ticket_id),
+                &AddReminderRequest {
+                    reminder: ReminderData { message, remind_at },
+                },
+            )
+            .await?;
+        Ok(resp.reminder)
+    }
+
+    /// List the reminders set on a request.
+    pub async fn list_reminders(
+        &self,
+        ticket_id: impl Into<TicketID>,
+    ) -> Result<Vec<Reminder>, Error> {
+        let ticket_id = ticket_id.into();
+        tracing::info!(ticket_id = %ticket_id, "listing reminders");
+        let resp: RemindersListResponse = self
+            .request_with_path(
+                Method::GET,
+                &format!("/api/v3/requests/{}/reminders", ticket_id),
+            )
+            .await?;
+        Ok(resp.reminders)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reminder_serializes_remind_at_as_epoch_millis() {
+        let body = AddReminderRequest {
+            reminder: ReminderData {
+                message: "Call back the customer",
+                remind_at: DateTime::from_timestamp_millis(1_700_000_000_000).unwrap(),
+            },
+        };
+
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            json!({
+                "reminder": {
+                    "message": "Call back the customer",
+                    "remind_at": { "value": 1_700_000_000_000_i64 },
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn reminder_keeps_sub_second_precision() {
+        let remind_at = DateTime::from_timestamp_millis(1_700_000_000_123).unwrap();
+        let data = ReminderData {
+            message: "",
+            remind_at,
+        };
+
+        let value = serde_json::to_value(&data).unwrap();
+
+        assert_eq!(value["remind_at"]["value"], json!(1_700_000_000_123_i64));
+    }
+
+    #[test]
+    fn reminder_deserializes_remind_at_time_entry() {
+        let reminder: Reminder = serde_json::from_value(json!({
+            "id": "5",
+            "message": "Call back the customer",
+            "remind_at": { "display_value": "Nov 14, 2023 10:13 PM", "value": "1700000000000" },
+        }))
+        .unwrap();
+
+        assert_eq!(reminder.remind_at.unwrap().value, "1700000000000");
+    }
+}
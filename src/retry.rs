@@ -0,0 +1,266 @@
+//! Retrying rate-limited and transiently failing requests.
+//!
+//! Enabled through [`ServiceDeskOptions::retry`](crate::ServiceDeskOptions::retry).
+//! A `Retry-After` header on the failed response decides the wait when present,
+//! otherwise the delay grows exponentially from [`RetryPolicy::base_delay`].
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::Method;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+
+use crate::error::{Error, SdpErrorCode};
+
+/// How failed requests are retried.
+///
+/// An SDP error code is retried unless it is in
+/// [`non_retryable`](Self::non_retryable). The default set holds every client
+/// code (`4000`-`4999`) except rate limiting (`4015`), plus `7001` (license
+/// restriction). That includes `4018`: requests locked by a running workflow
+/// fail straight away as [`Error::Locked`] so callers choose how long to back
+/// off; remove `4018` from the set to retry them instead.
+///
+/// A `4xxx` code means SDP rejected the request unprocessed, so it is retried
+/// for every method. Other codes, timeouts, dropped connections and gateway
+/// errors are retried only for `GET`, `HEAD`, `PUT`, `DELETE` and `OPTIONS`,
+/// since a `POST` that timed out may still have created its ticket or note.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt, `0` disables retrying
+    pub max_retries: u32,
+    /// Wait before the first retry, doubled for every retry after it
+    pub base_delay: Duration,
+    /// Upper bound on a single wait, including one asked for by `Retry-After`
+    pub max_delay: Duration,
+    /// SDP error codes that fail straight away, e.g. `4002` (forbidden).
+    ///
+    /// A lock recognised by its message counts as `4018`, and an HTTP 429
+    /// without an SDP body as `4015`.
+    pub non_retryable: HashSet<u32>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        let rate_limited = SdpErrorCode::RateLimitExceeded as u32;
+        RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            non_retryable: (4000..5000)
+                .filter(|&code| code != rate_limited)
+                .chain([SdpErrorCode::LicenseRestriction as u32])
+                .collect(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether a failed attempt is worth repeating.
+    ///
+    /// Without an SDP code, HTTP 4xx statuses other than 429 are not retried.
+    pub(crate) fn is_retryable(
+        &self,
+        method: &Method,
+        status: Option<u16>,
+        code: Option<u32>,
+        error: &Error,
+    ) -> bool {
+        let code = match error {
+            Error::Locked { .. } => Some(SdpErrorCode::RequestLocked as u32),
+            Error::RateLimited => Some(SdpErrorCode::RateLimitExceeded as u32),
+            _ if code.is_none() && status == Some(429) => {
+                Some(SdpErrorCode::RateLimitExceeded as u32)
+            }
+            _ => code,
+        };
+        if let Some(code) = code {
+            if self.non_retryable.contains(&code) {
+                return false;
+            }
+            return (4000..5000).contains(&code) || is_idempotent(method);
+        }
+        let transient = match status {
+            Some(400..=499) => return false,
+            Some(status) => matches!(status, 502..=504),
+            None => matches!(error, Error::Timeout(_) | Error::Connection(_)),
+        };
+        transient && is_idempotent(method)
+    }
+
+    /// Wait before retry number `retry` (starting at 0), preferring the
+    /// response's `Retry-After` header over exponential backoff.
+    pub(crate) fn delay(&self, retry: u32, headers: &HeaderMap, now: DateTime<Utc>) -> Duration {
+        headers
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| parse_retry_after(value, now))
+            .unwrap_or_else(|| self.backoff(retry))
+            .min(self.max_delay)
+    }
+
+    fn backoff(&self, retry: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay)
+    }
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
+    )
+}
+
+/// Parse a `Retry-After` value, either delta-seconds (`120`) or an HTTP-date
+/// (`Wed, 21 Oct 2015 07:28:00 GMT`). Dates in the past mean no wait.
+pub(crate) fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (at.with_timezone(&Utc) - now)
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use reqwest::header::HeaderValue;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2015, 10, 21, 7, 27, 0).unwrap()
+    }
+
+    fn headers(retry_after: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(retry_after).unwrap());
+        headers
+    }
+
+    fn sdp(code: u32) -> Error {
+        Error::Sdp {
+            code,
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn default_non_retryable_codes() {
+        let policy = RetryPolicy::default();
+        let get = &Method::GET;
+        let forbidden = Error::Forbidden("x".into());
+        assert!(!policy.is_retryable(get, Some(403), Some(4002), &forbidden));
+        assert!(!policy.is_retryable(get, Some(400), Some(7001), &Error::LicenseRestricted));
+        assert!(policy.is_retryable(get, Some(400), Some(4015), &Error::RateLimited));
+        assert!(!policy.is_retryable(get, Some(400), Some(4018), &sdp(4018)));
+    }
+
+    #[test]
+    fn non_retryable_membership_decides_every_code() {
+        let mut policy = RetryPolicy::default();
+        policy.non_retryable.remove(&4018);
+        policy.non_retryable.remove(&4001);
+        policy.non_retryable.insert(4015);
+        let locked = Error::Locked {
+            code: 4000,
+            message: "request is locked".into(),
+        };
+        let status_only = Error::UnexpectedResponse {
+            status: 429,
+            body: String::new(),
+        };
+        let post = &Method::POST;
+        assert!(policy.is_retryable(post, Some(400), Some(4018), &sdp(4018)));
+        assert!(policy.is_retryable(post, Some(400), Some(4000), &locked));
+        assert!(policy.is_retryable(post, Some(400), Some(4001), &sdp(4001)));
+        assert!(!policy.is_retryable(post, Some(400), Some(4015), &Error::RateLimited));
+        assert!(!policy.is_retryable(post, Some(429), None, &status_only));
+        assert!(!RetryPolicy::default().is_retryable(post, Some(400), Some(4000), &locked));
+    }
+
+    #[test]
+    fn codes_outside_the_client_range_retry_only_idempotent_methods() {
+        let policy = RetryPolicy::default();
+        assert!(policy.is_retryable(&Method::GET, Some(500), Some(5000), &sdp(5000)));
+        assert!(!policy.is_retryable(&Method::POST, Some(500), Some(5000), &sdp(5000)));
+
+        let mut policy = RetryPolicy::default();
+        policy.non_retryable.insert(5000);
+        assert!(!policy.is_retryable(&Method::GET, Some(500), Some(5000), &sdp(5000)));
+    }
+
+    #[test]
+    fn generic_and_unknown_client_codes_are_not_retried() {
+        let policy = RetryPolicy::default();
+        let get = &Method::GET;
+        assert!(!policy.is_retryable(get, Some(400), Some(4000), &sdp(4000)));
+        assert!(!policy.is_retryable(get, Some(400), Some(4042), &sdp(4042)));
+        let status_only = Error::UnexpectedResponse {
+            status: 400,
+            body: String::new(),
+        };
+        assert!(!policy.is_retryable(get, Some(400), None, &status_only));
+    }
+
+    #[test]
+    fn transport_and_gateway_errors_retry_only_idempotent_methods() {
+        let policy = RetryPolicy::default();
+        let unavailable = Error::UnexpectedResponse {
+            status: 503,
+            body: String::new(),
+        };
+        assert!(policy.is_retryable(&Method::PUT, Some(503), None, &unavailable));
+        assert!(!policy.is_retryable(&Method::POST, Some(503), None, &unavailable));
+        assert!(policy.is_retryable(&Method::POST, Some(429), None, &unavailable));
+        assert!(policy.is_retryable(&Method::POST, Some(400), Some(4015), &Error::RateLimited));
+    }
+
+    #[test]
+    fn retry_after_delta_seconds() {
+        let policy = RetryPolicy::default();
+        assert_eq!(
+            policy.delay(0, &headers("7"), now()),
+            Duration::from_secs(7)
+        );
+        assert_eq!(policy.delay(0, &headers("3600"), now()), policy.max_delay);
+    }
+
+    #[test]
+    fn retry_after_http_date() {
+        let policy = RetryPolicy::default();
+        assert_eq!(
+            policy.delay(0, &headers("Wed, 21 Oct 2015 07:27:20 GMT"), now()),
+            Duration::from_secs(20)
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now()),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn malformed_retry_after_falls_back_to_backoff() {
+        let policy = RetryPolicy::default();
+        assert_eq!(parse_retry_after("soon", now()), None);
+        assert_eq!(
+            policy.delay(0, &headers("soon"), now()),
+            Duration::from_millis(500)
+        );
+        assert_eq!(
+            policy.delay(2, &headers("-5"), now()),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            policy.delay(1, &HeaderMap::new(), now()),
+            Duration::from_secs(1)
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! Request tags (`/api/v3/requests/{id}/tags`).
+//!
+//! Tags are optional on SDP; instances without them answer the tags route with
+//! a plain 404 page, which is reported as [`Error::FeatureDisabled`].
+
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::{ServiceDesk, TicketID, error::Error};
+
+/// A tag attached to a request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tag {
+    pub id: Option<String>,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct TagRef<'a> {
+    name: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct TagsRequest<'a> {
+    tags: Vec<TagRef<'a>>,
+}
+
+impl<'a> TagsRequest<'a> {
+    fn new(tags: &[&'a str]) -> Self {
+        Self {
+            tags: tags.iter().map(|&name| TagRef { name }).collect(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct TagsResponse {
+    #[serde(default)]
+    tags: Vec<Tag>,
+}
+
+fn tags_path(ticket_id: TicketID) -> String {
+    format!("/api/v3/requests/{}/tags", ticket_id)
+}
+
+fn map_disabled(error: Error) -> Error {
+    match error {
+        Error::UnexpectedResponse { status: 404, .. } => {
+            Error::FeatureDisabled("tags are not enabled on this instance".into())
+        }
+        e => e,
+    }
+}
+
+impl ServiceDesk {
+    /// List the tags on a request.
+    pub async fn list_tags(&self, ticket_id: impl Into<TicketID>) -> Result<Vec<Tag>, Error> {
+        let ticket_id = ticket_id.into();
+        tracing::info!(ticket_id = %ticket_id, "listing tags");
+        let resp: TagsResponse = self
+            .request_with_path(Method::GET, &tags_path(ticket_id))
+            .await
+            .map_err(map_disabled)?;
+        Ok(resp.tags)
+    }
+
+    /// Add tags to a request, returning the request's tags afterwards.
+    pub async fn add_tags(
+        &self,
+        ticket_id: impl Into<TicketID>,
+        tags: &[&str],
+    ) -> Result<Vec<Tag>, Error> {
+        let ticket_id = ticket_id.into();
+        tracing::info!(ticket_id = %ticket_id, count = tags.len(), "adding tags");
+        let resp: TagsResponse = self
+            .request_input_data(Method::POST, &tags_path(ticket_id), &TagsRequest::new(tags))
+            .await
+            .map_err(map_disabled)?;
+        Ok(resp.tags)
+    }
+
+    /// Remove tags from a request. Tags the request doesn't carry are ignored by SDP.
+    pub async fn remove_tags(
+        &self,
+        ticket_id: impl Into<TicketID>,
+        tags: &[&str],
+    ) -> Result<(), Error> {
+        let ticket_id = ticket_id.into();
+        tracing::info!(ticket_id = %ticket_id, count = tags.len(), "removing tags");
+        let _: serde_json::Value = self
+            .request_input_data(
+                Method::DELETE,
+                &tags_path(ticket_id),
+                &TagsRequest::new(tags),
+            )
+            .await
+            .map_err(map_disabled)?;
+        Ok(())
+    }
+}
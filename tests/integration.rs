@@ -195,6 +195,8 @@ async fn edit_ticket() {
         requester: None,
         priority: Some(Priority::low()),
         udf_fields: None,
+        mode: None,
+        request_type: None,
     };
 
     let result = sdp.ticket(250225).edit(&editdata).await;
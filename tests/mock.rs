@@ -0,0 +1,2939 @@
+//! Tests against a local mock SDP server.
+//!
+//! These run by default and don't need a real SDP instance.
+
+use std::time::Duration;
+
+use reqwest::Url;
+use sdp_request_client::{
+    AnnouncementData, AssociatedItem, Association, ChangeData, Credentials, Criteria, NoteID,
+    ProblemData, RetryPolicy, ServiceDesk, ServiceDeskOptions, TicketID, TokenLocation,
+};
+use serde_json::{Value, json};
+use wiremock::matchers::{header, method, path, path_regex, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn setup() -> (MockServer, ServiceDesk) {
+    setup_with(ServiceDeskOptions::default()).await
+}
+
+async fn setup_with(options: ServiceDeskOptions) -> (MockServer, ServiceDesk) {
+    let server = MockServer::start().await;
+    let sdp = ServiceDesk::new(
+        Url::parse(&server.uri()).unwrap(),
+        Credentials::Token {
+            token: "test-token".into(),
+        },
+        options,
+    )
+    .expect("failed to build ServiceDesk client");
+    (server, sdp)
+}
+
+fn success_status() -> Value {
+    json!({ "status_code": 2000, "status": "success" })
+}
+
+fn ticket_json(id: u64, status: &str) -> Value {
+    json!({
+        "id": id.to_string(),
+        "subject": "Mock ticket",
+        "status": { "id": "2", "name": status, "color": null },
+        "created_by": { "id": "1", "name": "admin" },
+        "created_time": { "display_value": "", "value": "1700000000000" },
+    })
+}
+
+fn note_json(id: u64, description: &str) -> Value {
+    json!({ "id": id.to_string(), "description": description })
+}
+
+/// Decode the `input_data` JSON that SDP expects in the query string.
+fn input_data(request: &wiremock::Request) -> Value {
+    let (_, raw) = request
+        .url
+        .query_pairs()
+        .find(|(key, _)| key == "input_data")
+        .expect("request has no input_data");
+    serde_json::from_str(&raw).unwrap()
+}
+
+fn ticket_response(id: u64, status: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({
+        "request": ticket_json(id, status),
+        "response_status": success_status(),
+    }))
+}
+
+#[tokio::test]
+async fn wait_for_status_polls_until_target_reached() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(ticket_response(100, "Open"))
+        .up_to_n_times(2)
+        .with_priority(1)
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(ticket_response(100, "Resolved"))
+        .with_priority(2)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let status = sdp
+        .wait_for_status(
+            100,
+            "resolved",
+            Duration::from_millis(10),
+            Duration::from_secs(2),
+        )
+        .await
+        .unwrap();
+    assert_eq!(status.name, "Resolved");
+}
+
+#[tokio::test]
+async fn wait_for_status_times_out() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(ticket_response(100, "Open"))
+        .mount(&server)
+        .await;
+
+    let result = sdp
+        .wait_for_status(
+            100,
+            "Resolved",
+            Duration::from_millis(10),
+            Duration::from_millis(50),
+        )
+        .await;
+    assert!(matches!(result, Err(sdp_request_client::Error::Other(msg)) if msg == "timed out"));
+}
+
+#[tokio::test]
+async fn merge_preserving_copies_notes_before_merging() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/200/notes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "notes": [note_json(1, "first"), note_json(2, "second")],
+            "response_status": [success_status()],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v3/requests/100/notes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "note": note_json(3, "copy"),
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100/merge_requests"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "response_status": success_status() })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    sdp.ticket(100)
+        .merge_preserving(&[TicketID(200)])
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let copied: Vec<String> = requests
+        .iter()
+        .filter(|r| r.method.as_str() == "POST")
+        .map(|r| {
+            input_data(r)["note"]["description"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        })
+        .collect();
+    assert_eq!(
+        copied,
+        vec![
+            "[Copied from request 200]<br>first",
+            "[Copied from request 200]<br>second"
+        ]
+    );
+    assert_eq!(requests.last().unwrap().method.as_str(), "PUT");
+}
+
+#[tokio::test]
+async fn merge_preserving_copies_every_page_of_notes() {
+    let (server, sdp) = setup().await;
+    let first_page: Vec<Value> = (1..=100).map(|id| note_json(id, "old")).collect();
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/200/notes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "notes": first_page,
+            "list_info": { "has_more_rows": true, "start_index": 1, "row_count": 100 },
+            "response_status": [success_status()],
+        })))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/200/notes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "notes": [note_json(101, "latest")],
+            "list_info": { "has_more_rows": false, "start_index": 101, "row_count": 1 },
+            "response_status": [success_status()],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v3/requests/100/notes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "note": note_json(500, "copy"),
+        })))
+        .expect(101)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100/merge_requests"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "response_status": success_status() })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    sdp.ticket(100)
+        .merge_preserving(&[TicketID(200)])
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let last_copy = requests
+        .iter()
+        .rfind(|r| r.method.as_str() == "POST")
+        .unwrap();
+    assert_eq!(
+        input_data(last_copy)["note"]["description"],
+        "[Copied from request 200]<br>latest"
+    );
+    assert_eq!(input_data(&requests[1])["list_info"]["start_index"], 101);
+}
+
+#[tokio::test]
+async fn thread_includes_every_page_of_notes() {
+    let (server, sdp) = setup().await;
+    let first_page: Vec<Value> = (1..=100).map(|id| note_json(id, "old")).collect();
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100/notes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "notes": first_page,
+            "list_info": { "has_more_rows": true, "start_index": 1, "row_count": 100 },
+            "response_status": [success_status()],
+        })))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100/notes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "notes": [note_json(101, "latest")],
+            "list_info": { "has_more_rows": false, "start_index": 101, "row_count": 1 },
+            "response_status": [success_status()],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100/conversations"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "conversations": [] })))
+        .mount(&server)
+        .await;
+
+    let thread = sdp.ticket(100).thread().await.unwrap();
+
+    assert_eq!(thread.len(), 101);
+}
+
+#[tokio::test]
+async fn token_sent_in_authtoken_header_by_default() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100"))
+        .and(header("authtoken", "test-token"))
+        .respond_with(ticket_response(100, "Open"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    sdp.ticket(100).get().await.unwrap();
+}
+
+#[tokio::test]
+async fn token_sent_as_technician_key_query() {
+    let (server, sdp) = setup_with(ServiceDeskOptions {
+        token_location: TokenLocation::TechnicianKeyQuery,
+        ..Default::default()
+    })
+    .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100"))
+        .and(query_param("TECHNICIAN_KEY", "test-token"))
+        .respond_with(ticket_response(100, "Open"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    sdp.ticket(100).get().await.unwrap();
+    let requests = server.received_requests().await.unwrap();
+    assert!(!requests[0].headers.contains_key("authtoken"));
+}
+
+#[tokio::test]
+async fn token_sent_in_custom_header() {
+    let (server, sdp) = setup_with(ServiceDeskOptions {
+        token_location: TokenLocation::CustomHeader("X-SDP-Token".into()),
+        ..Default::default()
+    })
+    .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100"))
+        .and(header("x-sdp-token", "test-token"))
+        .respond_with(ticket_response(100, "Open"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    sdp.ticket(100).get().await.unwrap();
+    let requests = server.received_requests().await.unwrap();
+    assert!(!requests[0].headers.contains_key("authtoken"));
+}
+
+#[tokio::test]
+async fn attachment_links_for_keeps_per_ticket_results() {
+    let (server, sdp) = setup().await;
+    let mut ticket = ticket_json(100, "Open");
+    ticket["attachments"] = json!([{
+        "id": "1",
+        "name": "alert.log",
+        "content_url": "/api/v3/requests/100/attachments/1/download",
+    }]);
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "request": ticket,
+            "response_status": success_status(),
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100/conversations"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "conversations": [] })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/200"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+            "response_status": {
+                "status_code": 4000,
+                "status": "failed",
+                "messages": [{ "status_code": 4007, "type": "failed", "message": "Invalid URL" }],
+            },
+        })))
+        .mount(&server)
+        .await;
+
+    let links = sdp
+        .attachment_links_for(&[TicketID(100), TicketID(200)])
+        .await;
+
+    assert_eq!(links.len(), 2);
+    assert_eq!(
+        links[&TicketID(100)].as_ref().unwrap(),
+        &vec![format!(
+            "{}/api/v3/requests/100/attachments/1/download",
+            server.uri()
+        )]
+    );
+    assert!(matches!(
+        links[&TicketID(200)],
+        Err(sdp_request_client::Error::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn encode_input_data_matches_sent_query() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100/notes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "notes": [],
+            "response_status": [success_status()],
+        })))
+        .mount(&server)
+        .await;
+
+    sdp.list_notes(100, Some(10), Some(1)).await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let expected = sdp_request_client::encode_input_data(
+        &json!({ "list_info": { "row_count": 10, "start_index": 1 } }),
+    )
+    .unwrap();
+    assert_eq!(requests[0].url.query(), Some(expected.as_str()));
+}
+
+#[tokio::test]
+async fn my_tickets_searches_by_current_user() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/users/me"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "user": { "id": "1541", "name": "Tech One" },
+            "response_status": success_status(),
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "requests": [ticket_json(100, "Open")],
+            "list_info": { "has_more_rows": true, "start_index": 1, "row_count": 1 },
+            "response_status": [success_status()],
+        })))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "requests": [ticket_json(101, "Open")],
+            "list_info": { "has_more_rows": false, "start_index": 2, "row_count": 1 },
+            "response_status": [success_status()],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let tickets = sdp.tickets().mine().await.unwrap();
+    let ids: Vec<_> = tickets.iter().map(|t| t.id).collect();
+    assert_eq!(ids, [TicketID(100), TicketID(101)]);
+
+    let requests = server.received_requests().await.unwrap();
+    let criteria = &input_data(&requests[1])["list_info"]["search_criteria"];
+    assert_eq!(criteria["children"][0]["field"], "technician.id");
+    assert_eq!(criteria["children"][0]["value"], "1541");
+    assert_eq!(input_data(&requests[2])["list_info"]["start_index"], 2);
+}
+
+#[tokio::test]
+async fn fetch_page_returns_paging_info() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "requests": [ticket_json(101, "Open"), ticket_json(102, "Open")],
+            "list_info": {
+                "has_more_rows": true,
+                "start_index": 3,
+                "row_count": 2,
+                "total_count": 7,
+            },
+            "response_status": [success_status()],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let page = sdp
+        .tickets()
+        .search()
+        .open()
+        .limit(2)
+        .fetch_page(3)
+        .await
+        .unwrap();
+
+    assert_eq!(page.tickets.len(), 2);
+    assert_eq!(page.total_count, Some(7));
+    assert!(page.has_more_rows);
+    assert_eq!(page.start_index, 3);
+
+    let requests = server.received_requests().await.unwrap();
+    let list_info = &input_data(&requests[0])["list_info"];
+    assert_eq!(list_info["start_index"], 3);
+    assert_eq!(list_info["row_count"], 2);
+    assert_eq!(list_info["get_total_count"], true);
+}
+
+#[tokio::test]
+async fn idempotent_note_is_not_posted_twice() {
+    let (server, sdp) = setup().await;
+    let marked = "Contained<br>[idempotency-key: alert-42]";
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100/notes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "notes": [],
+            "response_status": [success_status()],
+        })))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100/notes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "notes": [note_json(5, marked)],
+            "response_status": [success_status()],
+        })))
+        .with_priority(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v3/requests/100/notes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "note": note_json(5, marked),
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // The retry finds the marker left by the first attempt and doesn't post again.
+    for _ in 0..2 {
+        let note = sdp
+            .ticket(100)
+            .note()
+            .description("Contained")
+            .idempotent("alert-42")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(note.id, NoteID(5));
+    }
+
+    let requests = server.received_requests().await.unwrap();
+    let posted = requests
+        .iter()
+        .find(|r| r.method.as_str() == "POST")
+        .unwrap();
+    assert_eq!(input_data(posted)["note"]["description"], marked);
+    assert_eq!(input_data(&requests[0])["list_info"]["sort_order"], "desc");
+}
+
+#[tokio::test]
+async fn list_announcements_parses_response() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/announcements"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "announcements": [{
+                "id": "7",
+                "title": "Maintenance window",
+                "description": "SDP will be down on Sunday",
+                "type": { "id": "1", "name": "General" },
+                "show_to": "all",
+                "from_date": { "display_value": "", "value": "1700000000000" },
+                "to_date": null,
+            }],
+            "response_status": [success_status()],
+        })))
+        .mount(&server)
+        .await;
+
+    let announcements = sdp.list_announcements().await.unwrap();
+
+    assert_eq!(announcements.len(), 1);
+    assert_eq!(announcements[0].title, "Maintenance window");
+    assert_eq!(
+        announcements[0].announcement_type.as_deref(),
+        Some("General")
+    );
+    assert_eq!(
+        announcements[0].from_date.as_ref().unwrap().value,
+        "1700000000000"
+    );
+}
+
+#[tokio::test]
+async fn create_announcement_sends_payload() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v3/announcements"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "announcement": { "id": "8", "title": "Phishing wave" },
+            "response_status": success_status(),
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let from = chrono::DateTime::from_timestamp_millis(1_700_000_000_000)
+        .unwrap()
+        .with_timezone(&chrono::Local);
+    let created = sdp
+        .create_announcement(&AnnouncementData {
+            title: "Phishing wave".into(),
+            announcement_type: Some("Security".into()),
+            show_to: Some("all".into()),
+            from_date: Some(from),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(created.id, "8");
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(
+        input_data(&requests[0]),
+        json!({
+            "announcement": {
+                "title": "Phishing wave",
+                "type": { "name": "Security" },
+                "show_to": "all",
+                "from_date": { "value": 1_700_000_000_000_i64 },
+            }
+        })
+    );
+}
+
+#[tokio::test]
+async fn no_timeout_does_not_fail_requests_instantly() {
+    let (server, sdp) = setup_with(ServiceDeskOptions {
+        timeout: None,
+        ..Default::default()
+    })
+    .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(ticket_response(100, "Open").set_delay(Duration::from_millis(200)))
+        .mount(&server)
+        .await;
+
+    let ticket = sdp.ticket(100).get().await.unwrap();
+    assert_eq!(ticket.id, TicketID(100));
+
+    // The same delay fails once a timeout shorter than it is configured.
+    let short = ServiceDesk::new(
+        Url::parse(&server.uri()).unwrap(),
+        Credentials::Token {
+            token: "test-token".into(),
+        },
+        ServiceDeskOptions {
+            timeout: Some(Duration::from_millis(50)),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let err = short.ticket(100).get().await.unwrap_err();
+    assert!(
+        matches!(err, sdp_request_client::Error::Timeout(_)),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn merge_reports_partial_failures() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100/merge_requests"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "response_status": [
+                { "id": "201", "status_code": 2000, "status": "success" },
+                {
+                    "id": "202",
+                    "status_code": 4000,
+                    "status": "failed",
+                    "messages": [{
+                        "status_code": 4002,
+                        "type": "failed",
+                        "message": "Operation not supported across accounts.",
+                    }],
+                },
+            ],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = sdp
+        .ticket(100)
+        .merge(&[TicketID(201), TicketID(202)])
+        .await
+        .unwrap();
+
+    assert_eq!(result.merged, vec![TicketID(201)]);
+    assert_eq!(result.failed.len(), 1);
+    assert_eq!(result.failed[0].0, TicketID(202));
+    assert!(result.failed[0].1.contains("across accounts"));
+}
+
+#[tokio::test]
+async fn merge_matches_id_less_entries_by_position() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100/merge_requests"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "response_status": [
+                { "status_code": 2000, "status": "success" },
+                {
+                    "status_code": 4000,
+                    "status": "failed",
+                    "messages": [{
+                        "status_code": 4002,
+                        "type": "failed",
+                        "message": "Operation not supported across accounts.",
+                    }],
+                },
+            ],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = sdp
+        .ticket(100)
+        .merge(&[TicketID(201), TicketID(202), TicketID(203)])
+        .await
+        .unwrap();
+
+    assert_eq!(result.merged, vec![TicketID(201)]);
+    assert_eq!(result.failed.len(), 2);
+    assert_eq!(result.failed[0].0, TicketID(202));
+    assert!(result.failed[0].1.contains("across accounts"));
+    assert_eq!(result.failed[1].0, TicketID(203));
+}
+
+#[tokio::test]
+async fn set_priority_sends_only_priority() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "response_status": success_status() })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    sdp.ticket(100).set_priority("High").await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    // Other fields are left out so SDP keeps their current values.
+    assert_eq!(
+        input_data(&requests[0]),
+        json!({ "request": { "priority": { "name": "High" } } })
+    );
+}
+
+#[tokio::test]
+async fn set_priority_maps_non_editable_field() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "response_status": {
+                "status_code": 4000,
+                "status": "failed",
+                "messages": [{ "status_code": 4009, "type": "failed", "message": "priority" }],
+            },
+        })))
+        .mount(&server)
+        .await;
+
+    let result = sdp.ticket(100).set_priority("High").await;
+    assert!(matches!(
+        result,
+        Err(sdp_request_client::Error::NotEditable(_))
+    ));
+}
+
+#[tokio::test]
+async fn list_trashed_requests_parses_summaries() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/trash"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "requests": [{
+                "id": "300",
+                "subject": "Duplicate alert",
+                "deleted_by": { "id": "1", "name": "admin" },
+                "deleted_time": { "display_value": "", "value": "1700000000000" },
+            }],
+            "response_status": [success_status()],
+        })))
+        .mount(&server)
+        .await;
+
+    let trashed = sdp.list_trashed_requests().await.unwrap();
+
+    assert_eq!(trashed.len(), 1);
+    assert_eq!(trashed[0].id, TicketID(300));
+    assert_eq!(trashed[0].deleted_by.as_ref().unwrap().name, "admin");
+}
+
+#[tokio::test]
+async fn close_builder_checks_closure_code() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/closure_codes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "closure_codes": [
+                { "id": "1", "name": "Resolved" },
+                { "id": "2", "name": "Cancelled" },
+            ],
+            "response_status": [success_status()],
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100/close"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "response_status": success_status() })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let unknown = sdp
+        .ticket(100)
+        .close_builder()
+        .code_checked("Postponed")
+        .await;
+    assert!(matches!(
+        unknown,
+        Err(sdp_request_client::Error::InvalidValue(_))
+    ));
+
+    sdp.ticket(100)
+        .close_builder()
+        .comment("done")
+        .code_checked("cancelled")
+        .await
+        .unwrap()
+        .send()
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let close = requests
+        .iter()
+        .find(|r| r.method.as_str() == "PUT")
+        .unwrap();
+    let body: Value = serde_json::from_slice(&close.body).unwrap();
+    assert_eq!(
+        body["request"]["closure_info"],
+        json!({ "closure_comments": "done", "closure_code": "Cancelled" })
+    );
+}
+
+/// Serves a fixed JSON body over TLS with the self-signed fixture certificate.
+async fn self_signed_tls_server() -> std::net::SocketAddr {
+    use std::sync::Arc;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio_rustls::rustls::{
+        self,
+        pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer},
+    };
+
+    let cert = CertificateDer::from(include_bytes!("fixtures/server-cert.der").to_vec());
+    let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(
+        include_bytes!("fixtures/server-key.der").to_vec(),
+    ));
+    let config = rustls::ServerConfig::builder_with_provider(Arc::new(
+        rustls::crypto::aws_lc_rs::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .unwrap()
+    .with_no_client_auth()
+    .with_single_cert(vec![cert], key)
+    .unwrap();
+    let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(config));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                let Ok(mut tls) = acceptor.accept(stream).await else {
+                    return;
+                };
+                let mut buf = [0u8; 4096];
+                let _ = tls.read(&mut buf).await;
+                let body = json!({ "response_status": success_status() }).to_string();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = tls.write_all(response.as_bytes()).await;
+                let _ = tls.shutdown().await;
+            });
+        }
+    });
+    addr
+}
+
+#[tokio::test]
+async fn default_options_reject_self_signed_certificate() {
+    let addr = self_signed_tls_server().await;
+    let base_url = Url::parse(&format!("https://{addr}")).unwrap();
+    let client = |options| {
+        ServiceDesk::new(
+            base_url.clone(),
+            Credentials::Token {
+                token: "test-token".into(),
+            },
+            options,
+        )
+        .unwrap()
+    };
+
+    let validating = client(ServiceDeskOptions::default());
+    assert!(validating.list_closure_codes().await.is_err());
+
+    let insecure = ServiceDesk::new_insecure(
+        base_url.clone(),
+        Credentials::Token {
+            token: "test-token".into(),
+        },
+        ServiceDeskOptions::default(),
+    )
+    .unwrap();
+    assert!(insecure.list_closure_codes().await.is_ok());
+}
+
+#[tokio::test]
+async fn new_refuses_options_accepting_invalid_certificates() {
+    let base_url = Url::parse("https://sdp.example.com").unwrap();
+    let credentials = || Credentials::Token {
+        token: "test-token".into(),
+    };
+    let unsafe_options = || ServiceDeskOptions {
+        security: Some(sdp_request_client::Security::Unsafe),
+        ..Default::default()
+    };
+
+    assert!(matches!(
+        ServiceDesk::new(base_url.clone(), credentials(), unsafe_options()),
+        Err(sdp_request_client::Error::InvalidValue(_))
+    ));
+    assert!(ServiceDesk::new_insecure(base_url, credentials(), unsafe_options()).is_ok());
+}
+
+#[tokio::test]
+async fn reopen_with_note_posts_note_then_reopens() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v3/requests/100/notes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "note": note_json(5, "Customer replied"),
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "response_status": success_status() })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    sdp.ticket(100)
+        .reopen_with_note("Customer replied")
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests[0].method.as_str(), "POST");
+    assert_eq!(requests[1].method.as_str(), "PUT");
+    assert_eq!(
+        input_data(&requests[1]),
+        json!({ "request": { "status": { "name": "Open" } } })
+    );
+}
+
+#[tokio::test]
+async fn reopen_with_note_stops_when_note_fails() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v3/requests/100/notes"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+            "response_status": {
+                "status_code": 4000,
+                "status": "failed",
+                "messages": [{ "status_code": 4007, "type": "failed", "message": "request" }],
+            },
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let result = sdp.ticket(100).reopen_with_note("Customer replied").await;
+
+    assert!(matches!(
+        result,
+        Err(sdp_request_client::Error::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn get_problem_parses_response() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/problems/12"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "problem": {
+                "id": "12",
+                "title": "Mail relay drops messages",
+                "status": { "id": "1", "name": "Open", "color": null },
+                "technician": { "id": "7", "name": "Jane" },
+                "created_time": { "display_value": "", "value": "1700000000000" },
+            },
+            "response_status": success_status(),
+        })))
+        .mount(&server)
+        .await;
+
+    let problem = sdp.get_problem("12").await.unwrap();
+
+    assert_eq!(problem.title, "Mail relay drops messages");
+    assert_eq!(problem.status.unwrap().name, "Open");
+    assert_eq!(problem.technician.unwrap().name, "Jane");
+    assert!(problem.requester.is_none());
+}
+
+#[tokio::test]
+async fn create_problem_sends_payload() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v3/problems"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "problem": { "id": "13", "title": "Disk alerts" },
+            "response_status": success_status(),
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let created = sdp
+        .create_problem(&ProblemData {
+            title: "Disk alerts".into(),
+            description: Some("Recurring disk full alerts".into()),
+            status: Some("Open".into()),
+            technician: Some("Jane".into()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(created.id, "13");
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(
+        input_data(&requests[0]),
+        json!({
+            "problem": {
+                "title": "Disk alerts",
+                "description": "Recurring disk full alerts",
+                "status": { "name": "Open" },
+                "technician": { "name": "Jane" },
+            }
+        })
+    );
+}
+
+#[tokio::test]
+async fn get_change_parses_response() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/changes/21"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "change": {
+                "id": "21",
+                "title": "Replace mail relay",
+                "status": { "id": "3", "name": "Planning", "color": null },
+                "change_type": { "id": "2", "name": "Normal" },
+                "risk": { "id": "1", "name": "High" },
+                "associated_requests": [{ "id": "100", "subject": "Mail is slow" }],
+            },
+            "response_status": success_status(),
+        })))
+        .mount(&server)
+        .await;
+
+    let change = sdp.get_change("21").await.unwrap();
+
+    assert_eq!(change.status.unwrap().name, "Planning");
+    assert_eq!(change.change_type.as_deref(), Some("Normal"));
+    assert_eq!(change.risk.as_deref(), Some("High"));
+    assert_eq!(change.associated_requests[0].id, TicketID(100));
+}
+
+#[tokio::test]
+async fn create_change_sends_payload() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v3/changes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "change": { "id": "22", "title": "Patch hypervisors" },
+            "response_status": success_status(),
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let created = sdp
+        .create_change(&ChangeData {
+            title: "Patch hypervisors".into(),
+            change_type: Some("Standard".into()),
+            risk: Some("Low".into()),
+            associated_requests: vec![TicketID(100), TicketID(101)],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(created.id, "22");
+    assert!(created.associated_requests.is_empty());
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(
+        input_data(&requests[0]),
+        json!({
+            "change": {
+                "title": "Patch hypervisors",
+                "change_type": { "name": "Standard" },
+                "risk": { "name": "Low" },
+                "associated_requests": [{ "id": "100" }, { "id": "101" }],
+            }
+        })
+    );
+}
+
+#[tokio::test]
+async fn associate_problem_links_request() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100/problem"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "problem": { "id": "12", "title": "Mail relay drops messages" },
+            "response_status": success_status(),
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let association = sdp.ticket(100).associate_problem("12").await.unwrap();
+
+    assert_eq!(
+        association,
+        Association::Linked(AssociatedItem {
+            id: "12".into(),
+            title: Some("Mail relay drops messages".into()),
+        })
+    );
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(
+        input_data(&requests[0]),
+        json!({ "problem": { "id": "12" } })
+    );
+}
+
+#[tokio::test]
+async fn tickets_associate_accepts_any_id_collection() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("PUT"))
+        .and(path_regex(r"^/api/v3/requests/\d+/problem$"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "problem": { "id": "12" },
+            "response_status": success_status(),
+        })))
+        .expect(3)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path_regex(r"^/api/v3/requests/\d+/change$"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "change": { "id": "7" },
+            "response_status": success_status(),
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let raw: &[u64] = &[1, 2];
+    let problems = sdp.tickets().associate_problem(raw, "12").await;
+    let changes = sdp
+        .tickets()
+        .associate_change(vec![TicketID(3), TicketID(4)], "7")
+        .await;
+    let ranged = sdp.tickets().associate_problem(5u64..=5, "12").await;
+
+    assert_eq!(problems.len(), 2);
+    assert_eq!(changes.len(), 2);
+    assert_eq!(ranged.len(), 1);
+    assert!(
+        problems
+            .iter()
+            .chain(&changes)
+            .chain(&ranged)
+            .all(Result::is_ok)
+    );
+    let mut paths: Vec<_> = server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .map(|r| r.url.path().to_string())
+        .collect();
+    paths.sort();
+    assert_eq!(
+        paths,
+        [
+            "/api/v3/requests/1/problem",
+            "/api/v3/requests/2/problem",
+            "/api/v3/requests/3/change",
+            "/api/v3/requests/4/change",
+            "/api/v3/requests/5/problem",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn associate_change_reports_existing_link() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100/change"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "response_status": {
+                "status_code": 4000,
+                "status": "failed",
+                "messages": [{ "status_code": 4005, "type": "failed", "message": "change" }],
+            },
+        })))
+        .mount(&server)
+        .await;
+
+    let association = sdp.ticket(100).associate_change("21").await.unwrap();
+
+    assert_eq!(association, Association::AlreadyLinked);
+}
+
+#[tokio::test]
+async fn created_in_last_uses_injected_clock() {
+    let (server, sdp) = setup().await;
+    let sdp =
+        sdp.with_clock(|| chrono::DateTime::from_timestamp_millis(1_700_000_000_000).unwrap());
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "requests": [],
+            "response_status": [success_status()],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    sdp.tickets()
+        .search()
+        .created_in_last(Duration::from_secs(3600))
+        .fetch()
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let criteria = &input_data(&requests[0])["list_info"]["search_criteria"];
+    assert_eq!(criteria["children"][0]["field"], "created_time");
+    assert_eq!(criteria["children"][0]["value"], "1699996400000");
+}
+
+#[tokio::test]
+async fn attachment_info_uses_head() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("HEAD"))
+        .and(path("/api/v3/requests/100/attachments/9/download"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "application/pdf")
+                .insert_header("content-length", "1048576"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let meta = sdp
+        .attachment_info("/api/v3/requests/100/attachments/9/download")
+        .await
+        .unwrap();
+
+    assert_eq!(meta.content_length, Some(1_048_576));
+    assert_eq!(meta.content_type.as_deref(), Some("application/pdf"));
+}
+
+#[tokio::test]
+async fn attachment_info_follows_retry_policy() {
+    let (server, sdp) = setup_with(ServiceDeskOptions {
+        retry: Some(RetryPolicy {
+            base_delay: Duration::from_millis(1),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+    .await;
+    Mock::given(method("HEAD"))
+        .and(path("/api/v3/requests/100/attachments/9/download"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("HEAD"))
+        .and(path("/api/v3/requests/100/attachments/9/download"))
+        .respond_with(ResponseTemplate::new(200).insert_header("content-length", "10"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let meta = sdp
+        .attachment_info("/api/v3/requests/100/attachments/9/download")
+        .await
+        .unwrap();
+
+    assert_eq!(meta.content_length, Some(10));
+}
+
+#[tokio::test]
+async fn all_attachment_links_requests_only_attachment_fields() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "request": {
+                "id": "100",
+                "attachments": [{
+                    "id": "1",
+                    "name": "alert.log",
+                    "content_url": "/api/v3/requests/100/attachments/1/download",
+                }],
+            },
+            "response_status": success_status(),
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100/conversations"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "conversations": [] })))
+        .mount(&server)
+        .await;
+
+    let links = sdp.ticket(100).all_attachment_links().await.unwrap();
+
+    assert_eq!(links.len(), 1);
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(
+        input_data(&requests[0]),
+        json!({ "fields_required": ["id", "attachments"] })
+    );
+}
+
+#[tokio::test]
+async fn all_attachment_links_fails_when_a_conversation_fails() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "request": { "id": "100", "attachments": [] },
+            "response_status": success_status(),
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100/conversations"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "conversations": [{
+                "id": "5",
+                "has_attachments": true,
+                "content_url": "/api/v3/requests/100/notifications/5",
+            }],
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100/notifications/5"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("unavailable"))
+        .mount(&server)
+        .await;
+
+    let err = sdp.ticket(100).all_attachment_links().await.unwrap_err();
+
+    assert!(
+        matches!(
+            err,
+            sdp_request_client::Error::UnexpectedResponse { status: 503, .. }
+        ),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn notes_pager_streams_all_pages() {
+    use futures_util::StreamExt;
+
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100/notes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "notes": [note_json(1, "first"), note_json(2, "second")],
+            "list_info": { "has_more_rows": true, "start_index": 1, "row_count": 2 },
+            "response_status": [success_status()],
+        })))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100/notes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "notes": [note_json(3, "third")],
+            "list_info": { "has_more_rows": false, "start_index": 3, "row_count": 1 },
+            "response_status": [success_status()],
+        })))
+        .with_priority(2)
+        .mount(&server)
+        .await;
+
+    let notes: Vec<_> = sdp.ticket(100).notes_pager(2).collect().await;
+
+    let ids: Vec<NoteID> = notes.into_iter().map(|note| note.unwrap().id).collect();
+    assert_eq!(ids, [NoteID(1), NoteID(2), NoteID(3)]);
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(input_data(&requests[1])["list_info"]["start_index"], 3);
+    assert_eq!(input_data(&requests[1])["list_info"]["row_count"], 2);
+}
+
+#[tokio::test]
+async fn search_tickets_with_threads_row_count() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "requests": [ticket_json(100, "Open")],
+            "response_status": [success_status()],
+        })))
+        .mount(&server)
+        .await;
+
+    sdp.search_tickets_with(Criteria::default(), 25)
+        .await
+        .unwrap();
+    sdp.search_tickets(Criteria::default()).await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(input_data(&requests[0])["list_info"]["row_count"], 25);
+    assert_eq!(input_data(&requests[1])["list_info"]["row_count"], 100);
+}
+
+#[tokio::test]
+async fn download_attachment_text_decodes_latin1() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100/attachments/9/download"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            vec![0x63, 0x61, 0x66, 0xe9],
+            "text/plain; charset=iso-8859-1",
+        ))
+        .mount(&server)
+        .await;
+
+    let text = sdp
+        .download_attachment_text("/api/v3/requests/100/attachments/9/download")
+        .await
+        .unwrap();
+
+    assert_eq!(text, "café");
+}
+
+fn gzip(body: &[u8]) -> Vec<u8> {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(body).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[tokio::test]
+async fn gzip_error_body_is_decoded() {
+    let (server, sdp) = setup().await;
+    let body = json!({
+        "response_status": {
+            "status_code": 4000,
+            "status": "failed",
+            "messages": [{ "status_code": 4007, "type": "failed", "message": "request" }],
+        },
+    });
+    Mock::given(method("GET"))
+        .and(path("/api/v3/problems/404"))
+        .respond_with(
+            ResponseTemplate::new(404)
+                .insert_header("content-encoding", "gzip")
+                .set_body_raw(gzip(body.to_string().as_bytes()), "application/json"),
+        )
+        .mount(&server)
+        .await;
+
+    let result = sdp.get_problem("404").await;
+
+    assert!(matches!(
+        result,
+        Err(sdp_request_client::Error::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn gzip_non_sdp_error_body_is_kept_decoded() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/problems/1"))
+        .respond_with(
+            ResponseTemplate::new(502)
+                .insert_header("content-encoding", "gzip")
+                .set_body_raw(gzip(b"<html>Bad Gateway</html>"), "text/html"),
+        )
+        .mount(&server)
+        .await;
+
+    let result = sdp.get_problem("1").await;
+
+    match result {
+        Err(sdp_request_client::Error::UnexpectedResponse { status, body }) => {
+            assert_eq!(status, 502);
+            assert_eq!(body, "<html>Bad Gateway</html>");
+        }
+        other => panic!("expected UnexpectedResponse, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn reclassify_sends_request_type() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "response_status": success_status() })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    sdp.ticket(100).reclassify("Service Request").await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(
+        input_data(&requests[0]),
+        json!({ "request": { "request_type": { "name": "Service Request" } } })
+    );
+}
+
+#[tokio::test]
+async fn reclassify_maps_template_mismatch() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "response_status": {
+                "status_code": 4000,
+                "status": "failed",
+                "messages": [{ "status_code": 4001, "type": "failed", "message": "request_type" }],
+            },
+        })))
+        .mount(&server)
+        .await;
+
+    let result = sdp.ticket(100).reclassify("Service Request").await;
+
+    match result {
+        Err(sdp_request_client::Error::InvalidValue(msg)) => {
+            assert!(msg.contains("\"Service Request\""));
+            assert!(msg.contains("request_type"));
+        }
+        other => panic!("expected InvalidValue, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn approval_status_walks_all_levels() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100/approval_levels"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "approval_levels": [{ "id": "1" }, { "id": "2" }],
+            "response_status": success_status(),
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100/approval_levels/1/approvals"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "approvals": [{ "id": "10", "status": { "name": "Approved" } }],
+            "response_status": success_status(),
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100/approval_levels/2/approvals"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "approvals": [{ "id": "20", "status": { "name": "Pending Approval" } }],
+            "response_status": success_status(),
+        })))
+        .mount(&server)
+        .await;
+
+    let status = sdp.ticket(100).approval_status().await.unwrap();
+
+    assert_eq!(status, sdp_request_client::ApprovalStatus::Pending);
+}
+
+async fn mount_users(server: &MockServer, users: Value) {
+    Mock::given(method("GET"))
+        .and(path("/api/v3/users"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "users": users,
+            "response_status": success_status(),
+        })))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn resolve_requester_prefers_exact_match() {
+    let (server, sdp) = setup().await;
+    mount_users(
+        &server,
+        json!([
+            { "id": "1", "name": "John Doe", "email_id": "john@example.com" },
+            { "id": "2", "name": "John Doey", "email_id": "doey@example.com" },
+        ]),
+    )
+    .await;
+
+    let user = sdp.resolve_requester("JOHN@example.com").await.unwrap();
+
+    assert_eq!(user.id.0, "1");
+    let requests = server.received_requests().await.unwrap();
+    let criteria = &input_data(&requests[0])["list_info"]["search_criteria"];
+    assert_eq!(criteria["field"], "name");
+    assert_eq!(criteria["condition"], "contains");
+    assert_eq!(criteria["children"][0]["field"], "email_id");
+    assert_eq!(criteria["children"][0]["logical_operator"], "OR");
+}
+
+#[tokio::test]
+async fn resolve_requester_rejects_ambiguous_match() {
+    let (server, sdp) = setup().await;
+    mount_users(
+        &server,
+        json!([
+            { "id": "1", "name": "John Doe" },
+            { "id": "2", "name": "John Doey" },
+        ]),
+    )
+    .await;
+
+    let result = sdp.resolve_requester("John").await;
+
+    match result {
+        Err(sdp_request_client::Error::NotUnique(msg)) => {
+            assert!(msg.contains("John Doe, John Doey"));
+        }
+        other => panic!("expected NotUnique, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn resolve_requester_reports_missing_user() {
+    let (server, sdp) = setup().await;
+    mount_users(&server, json!([])).await;
+
+    let result = sdp.resolve_requester("Nobody").await;
+
+    assert!(matches!(
+        result,
+        Err(sdp_request_client::Error::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn list_notes_surfaces_failed_response_status() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100/notes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "notes": [],
+            "response_status": [{
+                "status_code": 4000,
+                "status": "failed",
+                "messages": [{ "status_code": 4007, "type": "failed", "message": "Invalid URL" }],
+            }],
+        })))
+        .mount(&server)
+        .await;
+
+    let result = sdp.list_notes(100, None, None).await;
+
+    assert!(matches!(
+        result,
+        Err(sdp_request_client::Error::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn bulk_close_keeps_per_ticket_results_in_order() {
+    let (server, sdp) = setup().await;
+    for id in [1, 3] {
+        Mock::given(method("PUT"))
+            .and(path(format!("/api/v3/requests/{id}/close")))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({ "response_status": success_status() })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/2/close"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+            "response_status": {
+                "status_code": 4000,
+                "status": "failed",
+                "messages": [{ "status_code": 4007, "type": "failed", "message": "request" }],
+            },
+        })))
+        .mount(&server)
+        .await;
+
+    let ids = [TicketID(1), TicketID(2), TicketID(3)];
+    let results = sdp.bulk_close(&ids, "auto-resolved", "Resolved").await;
+
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_ok());
+    assert!(matches!(
+        results[1],
+        Err(sdp_request_client::Error::NotFound(_))
+    ));
+    assert!(results[2].is_ok());
+    let requests = server.received_requests().await.unwrap();
+    assert!(requests.iter().all(|r| {
+        serde_json::from_slice::<Value>(&r.body).unwrap()["request"]["closure_info"]["closure_code"]
+            == "Resolved"
+    }));
+}
+
+#[tokio::test]
+async fn ticket_details_maps_trashed_ticket_to_not_found() {
+    let (server, sdp) = setup().await;
+    let mut ticket = ticket_json(100, "Open");
+    ticket["is_trashed"] = json!(true);
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "request": ticket,
+            "response_status": success_status(),
+        })))
+        .mount(&server)
+        .await;
+
+    let result = sdp.ticket_details(100).await;
+
+    match result {
+        Err(sdp_request_client::Error::NotFound(msg)) => assert!(msg.contains("trash")),
+        other => panic!("expected NotFound, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn status_breakdown_counts_each_status() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/statuses"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "statuses": [
+                { "id": "2", "name": "Open" },
+                { "id": "3", "name": "Onhold" },
+                { "id": "4", "name": "Resolved" },
+            ],
+            "response_status": [success_status()],
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests"))
+        .respond_with(|request: &wiremock::Request| {
+            let list_info = &input_data(request)["list_info"];
+            let total = match list_info["search_criteria"]["value"].as_str() {
+                Some("Open") => 5,
+                Some("Onhold") => 3,
+                _ => 0,
+            };
+            ResponseTemplate::new(200).set_body_json(json!({
+                "requests": [],
+                "list_info": { "total_count": total },
+                "response_status": [success_status()],
+            }))
+        })
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let base = Criteria {
+        field: "group.name".to_string(),
+        value: "SOC".into(),
+        ..Default::default()
+    };
+    let counts = sdp.status_breakdown(base).await.unwrap();
+
+    assert_eq!(counts.len(), 3);
+    assert_eq!(counts["Open"], 5);
+    assert_eq!(counts["Onhold"], 3);
+    assert_eq!(counts["Resolved"], 0);
+    let requests = server.received_requests().await.unwrap();
+    let criteria = &input_data(requests.last().unwrap())["list_info"]["search_criteria"];
+    assert_eq!(criteria["field"], "status.name");
+    assert_eq!(criteria["children"][0]["field"], "group.name");
+    assert_eq!(criteria["children"][0]["logical_operator"], "AND");
+    assert_eq!(
+        input_data(requests.last().unwrap())["list_info"]["get_total_count"],
+        true
+    );
+}
+
+#[tokio::test]
+async fn empty_trash_sends_when_count_confirmed() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("DELETE"))
+        .and(path("/api/v3/requests/trash"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "response_status": success_status() })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    sdp.tickets()
+        .empty_trash([300u64, 301])
+        .confirm(2)
+        .send()
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(input_data(&requests[0]), json!({ "ids": ["300", "301"] }));
+}
+
+#[tokio::test]
+async fn empty_trash_refuses_mismatched_or_missing_confirmation() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("DELETE"))
+        .and(path("/api/v3/requests/trash"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let mismatched = sdp
+        .tickets()
+        .empty_trash([300u64, 301, 302])
+        .confirm(2)
+        .send()
+        .await;
+    let missing = sdp.tickets().empty_trash([300u64]).send().await;
+
+    assert!(matches!(
+        mismatched,
+        Err(sdp_request_client::Error::InvalidValue(_))
+    ));
+    assert!(matches!(
+        missing,
+        Err(sdp_request_client::Error::InvalidValue(_))
+    ));
+}
+
+#[tokio::test]
+async fn tags_are_listed_added_and_removed() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100/tags"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "tags": [{ "id": "1", "name": "phishing" }],
+            "response_status": success_status(),
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v3/requests/100/tags"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "tags": [{ "id": "1", "name": "phishing" }, { "id": "2", "name": "vip" }],
+            "response_status": success_status(),
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/api/v3/requests/100/tags"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "response_status": success_status() })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let ticket = sdp.ticket(100);
+    let tags = ticket.tags().await.unwrap();
+    assert_eq!(tags.len(), 1);
+    assert_eq!(tags[0].name, "phishing");
+
+    let tags = ticket.add_tags(&["vip"]).await.unwrap();
+    assert_eq!(tags.len(), 2);
+    ticket.remove_tags(&["phishing"]).await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(
+        input_data(&requests[1]),
+        json!({ "tags": [{ "name": "vip" }] })
+    );
+    assert_eq!(
+        input_data(&requests[2]),
+        json!({ "tags": [{ "name": "phishing" }] })
+    );
+}
+
+#[tokio::test]
+async fn tags_report_disabled_feature() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100/tags"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("<html>Not Found</html>"))
+        .mount(&server)
+        .await;
+
+    let result = sdp.ticket(100).tags().await;
+
+    assert!(matches!(
+        result,
+        Err(sdp_request_client::Error::FeatureDisabled(_))
+    ));
+}
+
+#[tokio::test]
+async fn edit_reports_field_failures_despite_success_status() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "request": ticket_json(100, "Open"),
+            "response_status": {
+                "status_code": 2000,
+                "status": "success",
+                "messages": [
+                    { "status_code": 2000, "type": "success", "message": "updated" },
+                    {
+                        "status_code": 4001,
+                        "type": "failed",
+                        "field": "udf_sline_1",
+                        "message": "Invalid value",
+                    },
+                ],
+            },
+        })))
+        .mount(&server)
+        .await;
+
+    let data = sdp_request_client::EditTicketData {
+        subject: "Mock ticket".to_string(),
+        status: sdp_request_client::Status::open(),
+        description: None,
+        requester: None,
+        priority: None,
+        udf_fields: Some(json!({ "udf_sline_1": "bad" })),
+        mode: None,
+        request_type: None,
+    };
+    let result = sdp.edit(100, &data).await;
+
+    match result {
+        Err(sdp_request_client::Error::Validation(errors)) => {
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].field.as_deref(), Some("udf_sline_1"));
+            assert_eq!(errors[0].code, 4001);
+        }
+        other => panic!("expected Validation, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn reply_reports_rejected_fields() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v3/requests/100/notifications"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "response_status": {
+                "status_code": 2000,
+                "status": "success",
+                "messages": [{
+                    "status_code": 4001,
+                    "type": "failed",
+                    "field": "cc",
+                    "message": "Invalid value",
+                }],
+            },
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = sdp
+        .ticket(100)
+        .reply()
+        .to("user@example.com")
+        .cc("not-an-address")
+        .subject("Re: Mock ticket")
+        .description("Done.")
+        .send()
+        .await;
+
+    match result {
+        Err(sdp_request_client::Error::Validation(errors)) => {
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].field.as_deref(), Some("cc"));
+        }
+        other => panic!("expected Validation, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn get_by_reference_returns_unique_match() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "requests": [ticket_json(100, "Open")],
+            "response_status": [success_status()],
+        })))
+        .mount(&server)
+        .await;
+
+    let ticket = sdp.get_by_reference("SR-1042").await.unwrap();
+
+    assert_eq!(ticket.id, TicketID(100));
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(
+        input_data(&requests[0])["list_info"]["search_criteria"],
+        json!({ "field": "display_id", "condition": "is", "value": "SR-1042" })
+    );
+}
+
+#[tokio::test]
+async fn get_by_reference_rejects_ambiguous_match() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "requests": [ticket_json(100, "Open"), ticket_json(101, "Open")],
+            "response_status": [success_status()],
+        })))
+        .mount(&server)
+        .await;
+
+    let result = sdp.get_by_reference("SR-1042").await;
+
+    assert!(matches!(
+        result,
+        Err(sdp_request_client::Error::NotUnique(_))
+    ));
+}
+
+#[tokio::test]
+async fn reopen_forced_sends_bypass_flag() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "response_status": success_status() })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    sdp.ticket(100).reopen_forced().await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(
+        input_data(&requests[0]),
+        json!({ "request": { "status": { "name": "Open" }, "force_reopen": true } })
+    );
+}
+
+#[tokio::test]
+async fn reopen_forced_unsupported_is_closure_rule_violation() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "response_status": {
+                "status_code": 4000,
+                "status": "failed",
+                "messages": [{ "status_code": 4011, "type": "failed", "message": "force_reopen" }],
+            },
+        })))
+        .mount(&server)
+        .await;
+
+    let result = sdp.ticket(100).reopen_forced().await;
+
+    match result {
+        Err(sdp_request_client::Error::ClosureRuleViolation(msg)) => {
+            assert!(msg.contains("not supported"));
+        }
+        other => panic!("expected ClosureRuleViolation, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn edit_of_locked_request_maps_to_locked() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "response_status": {
+                "status_code": 4000,
+                "status": "failed",
+                "messages": [{
+                    "status_code": 4018,
+                    "type": "failed",
+                    "message": "Request is being processed by a workflow",
+                }],
+            },
+        })))
+        .mount(&server)
+        .await;
+
+    let result = sdp
+        .patch(100, json!({ "priority": { "name": "High" } }))
+        .await;
+
+    match result {
+        Err(sdp_request_client::Error::Locked { code, message }) => {
+            assert_eq!(code, 4018);
+            assert!(message.contains("workflow"));
+        }
+        other => panic!("expected Locked, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn search_windowed_queries_each_window_and_dedups() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "requests": [ticket_json(100, "Open")],
+            "response_status": [success_status()],
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let from = chrono::DateTime::from_timestamp_millis(1_700_000_000_000).unwrap();
+    let to = from + chrono::Duration::hours(2);
+    let base = Criteria {
+        field: "id".to_string(),
+        condition: sdp_request_client::Condition::GreaterThan,
+        value: "0".into(),
+        ..Default::default()
+    };
+    let tickets = sdp
+        .search_windowed(base, "created_time", from, to, Duration::from_secs(3600))
+        .await
+        .unwrap();
+
+    assert_eq!(tickets.len(), 1);
+    let requests = server.received_requests().await.unwrap();
+    let second = &input_data(&requests[1])["list_info"]["search_criteria"]["children"];
+    assert_eq!(second[0]["condition"], "greater than");
+    assert_eq!(second[0]["value"], "1700003599999");
+    assert_eq!(second[1]["condition"], "lesser than");
+    assert_eq!(second[1]["value"], "1700007200001");
+}
+
+#[tokio::test]
+async fn has_attachments_requests_only_the_flag() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "request": { "id": "100", "has_attachments": true },
+            "response_status": success_status(),
+        })))
+        .mount(&server)
+        .await;
+
+    assert!(sdp.ticket(100).has_attachments().await.unwrap());
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(
+        input_data(&requests[0]),
+        json!({ "fields_required": ["id", "has_attachments"] })
+    );
+}
+
+#[tokio::test]
+async fn resolve_and_close_sets_resolution_before_closing() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100/resolutions"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "response_status": success_status() })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100/close"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "response_status": success_status() })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    sdp.ticket(100)
+        .resolve_and_close("False positive", "auto-resolved", "Resolved")
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests[0].url.path(), "/api/v3/requests/100/resolutions");
+    assert_eq!(
+        input_data(&requests[0]),
+        json!({ "resolution": { "content": "False positive" } })
+    );
+    assert_eq!(requests[1].url.path(), "/api/v3/requests/100/close");
+    let close: Value = serde_json::from_slice(&requests[1].body).unwrap();
+    assert_eq!(close["request"]["closure_info"]["closure_code"], "Resolved");
+}
+
+#[tokio::test]
+async fn user_agent_suffix_is_appended_to_default() {
+    let (server, sdp) = setup_with(ServiceDeskOptions {
+        user_agent_suffix: Some("my-app/1.2".to_string()),
+        ..Default::default()
+    })
+    .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100"))
+        .and(header("user-agent", "servicedesk-rs/0.1.0 my-app/1.2"))
+        .respond_with(ticket_response(100, "Open"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    sdp.ticket_details(100).await.unwrap();
+}
+
+#[tokio::test]
+async fn server_info_reads_build_and_api_version() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/app_resources/build_info"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "build_info": { "build_number": "14300", "version": "14.3", "api_version": "v3" },
+            "response_status": success_status(),
+        })))
+        .mount(&server)
+        .await;
+
+    let info = sdp.server_info().await.unwrap();
+
+    assert_eq!(info.build_number, 14300);
+    assert_eq!(info.version.as_deref(), Some("14.3"));
+    assert_eq!(info.api_version.as_deref(), Some("v3"));
+}
+
+#[tokio::test]
+async fn find_open_by_subject_searches_exact_open_subject() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "requests": [ticket_json(100, "Open")],
+            "response_status": [success_status()],
+        })))
+        .mount(&server)
+        .await;
+
+    let found = sdp
+        .tickets()
+        .find_open_by_subject("[ALERT] disk full on db-1")
+        .await
+        .unwrap();
+
+    assert_eq!(found.unwrap().id, TicketID(100));
+    let requests = server.received_requests().await.unwrap();
+    let list_info = &input_data(&requests[0])["list_info"];
+    assert_eq!(list_info["row_count"], 1);
+    assert_eq!(
+        list_info["search_criteria"],
+        json!({
+            "field": "status.name",
+            "condition": "is",
+            "value": "Open",
+            "children": [{
+                "field": "subject",
+                "condition": "is",
+                "value": "[ALERT] disk full on db-1",
+                "logical_operator": "AND",
+            }],
+        })
+    );
+}
+
+#[tokio::test]
+async fn rate_limited_request_is_retried_after_retry_after() {
+    let (server, sdp) = setup_with(ServiceDeskOptions {
+        retry: Some(RetryPolicy::default()),
+        ..Default::default()
+    })
+    .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .insert_header("retry-after", "0")
+                .set_body_string("Too Many Requests"),
+        )
+        .up_to_n_times(1)
+        .with_priority(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(ticket_response(100, "Open"))
+        .with_priority(2)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let ticket = sdp.ticket_details(100).await.unwrap();
+    assert_eq!(ticket.id, TicketID(100));
+}
+
+#[tokio::test]
+async fn forbidden_response_is_not_retried() {
+    let (server, sdp) = setup_with(ServiceDeskOptions {
+        retry: Some(RetryPolicy::default()),
+        ..Default::default()
+    })
+    .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(ResponseTemplate::new(403).set_body_json(json!({
+            "response_status": {
+                "status_code": 4000,
+                "status": "failed",
+                "messages": [{ "status_code": 4002, "type": "failed", "message": "Access denied" }],
+            },
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = sdp.ticket_details(100).await.unwrap_err();
+    assert!(
+        matches!(err, sdp_request_client::Error::Forbidden(_)),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn generic_client_error_is_not_retried() {
+    let (server, sdp) = setup_with(ServiceDeskOptions {
+        retry: Some(RetryPolicy {
+            base_delay: Duration::from_millis(1),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+    .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "response_status": { "status_code": 4000, "status": "failed" },
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = sdp.ticket_details(100).await.unwrap_err();
+    assert!(
+        matches!(err, sdp_request_client::Error::Sdp { code: 4000, .. }),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn failed_post_is_not_resent() {
+    let (server, sdp) = setup_with(ServiceDeskOptions {
+        retry: Some(RetryPolicy {
+            base_delay: Duration::from_millis(1),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+    .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v3/requests/100/notes"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = sdp.ticket(100).add_note("disk full").await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            sdp_request_client::Error::UnexpectedResponse { status: 503, .. }
+        ),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn attachment_count_counts_unique_links_across_conversations() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "request": {
+                "id": "100",
+                "attachments": [{
+                    "id": "1",
+                    "name": "alert.log",
+                    "content_url": "/api/v3/requests/100/attachments/1/download",
+                }],
+            },
+            "response_status": success_status(),
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100/conversations"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "conversations": [{
+                "id": "5",
+                "has_attachments": true,
+                "content_url": "/api/v3/requests/100/notifications/5",
+            }],
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100/notifications/5"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "notification": {
+                "attachments": [
+                    {
+                        "id": "1",
+                        "name": "alert.log",
+                        "content_url": format!("{}/api/v3/requests/100/attachments/1/download", server.uri()),
+                    },
+                    {
+                        "id": "2",
+                        "name": "screenshot.png",
+                        "content_url": "/api/v3/requests/100/attachments/2/download",
+                    },
+                ],
+            },
+        })))
+        .mount(&server)
+        .await;
+
+    let count = sdp.ticket(100).attachment_count().await.unwrap();
+
+    assert_eq!(count, 2);
+}
+
+#[tokio::test]
+async fn escalate_reopens_assigns_and_notes() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(ticket_response(100, "Closed"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "response_status": success_status() })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100/assign"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "response_status": success_status() })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v3/requests/100/notes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "note": note_json(5, "Escalated to L2"),
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    sdp.ticket(100)
+        .escalate("L2 Tech", "Escalated to L2")
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let steps: Vec<_> = requests
+        .iter()
+        .map(|r| format!("{} {}", r.method, r.url.path()))
+        .collect();
+    assert_eq!(
+        steps,
+        [
+            "GET /api/v3/requests/100",
+            "PUT /api/v3/requests/100",
+            "PUT /api/v3/requests/100/assign",
+            "POST /api/v3/requests/100/notes",
+        ]
+    );
+    assert_eq!(
+        input_data(&requests[2]),
+        json!({ "request": { "technician": { "name": "L2 Tech" } } })
+    );
+    assert_eq!(input_data(&requests[3])["note"]["show_to_requester"], false);
+}
+
+#[tokio::test]
+async fn move_request_sends_site_and_group() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100/_move"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "response_status": success_status() })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    sdp.ticket(100)
+        .move_to(Some("Berlin"), Some("Network"))
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(
+        input_data(&requests[0]),
+        json!({ "request": { "site": { "name": "Berlin" }, "group": { "name": "Network" } } })
+    );
+}
+
+#[tokio::test]
+async fn move_request_reports_mandatory_fields() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100/_move"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "response_status": {
+                "status_code": 4000,
+                "status": "failed",
+                "messages": [{ "status_code": 4012, "type": "failed", "message": "category" }],
+            },
+        })))
+        .mount(&server)
+        .await;
+
+    let err = sdp
+        .move_request(100, None, Some("Network"))
+        .await
+        .unwrap_err();
+
+    match err {
+        sdp_request_client::Error::MissingField(detail) => {
+            assert_eq!(detail, "category (mandatory in group \"Network\")")
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+    assert!(matches!(
+        sdp.move_request(100, None, None).await,
+        Err(sdp_request_client::Error::InvalidValue(_))
+    ));
+}
+
+#[tokio::test]
+async fn escalate_priority_patches_next_level() {
+    let (server, sdp) = setup().await;
+    let mut ticket = ticket_json(100, "Open");
+    ticket["priority"] = json!({ "id": "4", "name": "High", "color": "#ff5e00" });
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "request": ticket,
+            "response_status": success_status(),
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "response_status": success_status() })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let priority = sdp.ticket(100).escalate_priority().await.unwrap();
+
+    assert_eq!(priority.name, "Critical");
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(
+        input_data(&requests[1]),
+        json!({ "request": { "priority": { "id": "301" } } })
+    );
+}
+
+#[tokio::test]
+async fn search_raw_returns_echoed_list_info() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "requests": [ticket_json(100, "Open"), ticket_json(101, "Open")],
+            "list_info": {
+                "has_more_rows": true,
+                "page": 3,
+                "row_count": 2,
+                "sort_field": "created_time",
+                "sort_order": "desc",
+                "start_index": 5,
+                "total_count": 42,
+            },
+            "response_status": [success_status()],
+        })))
+        .mount(&server)
+        .await;
+
+    let (tickets, list_info) = sdp.search_raw(Criteria::default(), 2, 5).await.unwrap();
+
+    assert_eq!(tickets.len(), 2);
+    assert_eq!(list_info.page, 3);
+    assert_eq!(list_info.start_index, 5);
+    assert_eq!(list_info.total_count, Some(42));
+    assert!(list_info.has_more_rows);
+    assert_eq!(list_info.sort_field, "created_time");
+    let requests = server.received_requests().await.unwrap();
+    let sent = &input_data(&requests[0])["list_info"];
+    assert_eq!(sent["row_count"], 2);
+    assert_eq!(sent["start_index"], 5);
+}
+
+#[tokio::test]
+async fn create_from_template_applies_defaults() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/request_templates"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "request_templates": [{ "id": "5", "name": "Hardware" }],
+            "response_status": [success_status()],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/request_templates/5"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "request_template": {
+                "id": "5",
+                "name": "Hardware",
+                "request": {
+                    "subject": "Hardware request",
+                    "priority": { "id": "3", "name": "High" },
+                    "udf_fields": { "udf_sline_1": "Laptop" },
+                },
+                "udf_fields": [
+                    { "key": "udf_sline_2", "mandatory": true, "field_type": "text" },
+                ],
+            },
+            "response_status": success_status(),
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v3/requests"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "request": {
+                "id": "100",
+                "subject": "Hardware request",
+                "status": { "id": "2", "name": "Open", "color": null },
+                "created_time": { "display_value": "", "value": "1700000000000" },
+                "account": { "id": "1", "name": "Acme" },
+                "template": { "id": "5", "name": "Hardware" },
+            },
+            "response_status": success_status(),
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    sdp.tickets()
+        .create()
+        .from_template("Hardware")
+        .await
+        .unwrap()
+        .requester("Jane")
+        .send()
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let lookup = &input_data(&requests[0])["list_info"]["search_criteria"];
+    assert_eq!(lookup["value"], "Hardware");
+    let request = &input_data(&requests[2])["request"];
+    assert_eq!(request["subject"], "Hardware request");
+    assert_eq!(request["template"]["name"], "Hardware");
+    assert_eq!(request["priority"]["name"], "High");
+    assert_eq!(request["udf_fields"]["udf_sline_1"], "Laptop");
+    assert!(request["udf_fields"]["udf_sline_2"].is_null());
+    assert!(
+        request["udf_fields"]
+            .as_object()
+            .unwrap()
+            .contains_key("udf_sline_2")
+    );
+}
+
+#[tokio::test]
+async fn requests_for_requester_fetches_every_page() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "requests": [ticket_json(101, "Open"), ticket_json(102, "Closed")],
+            "list_info": { "has_more_rows": true, "start_index": 1, "row_count": 2 },
+            "response_status": [success_status()],
+        })))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "requests": [ticket_json(103, "Open")],
+            "list_info": { "has_more_rows": false, "start_index": 101, "row_count": 1 },
+            "response_status": [success_status()],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let tickets = sdp.requests_for_requester("Jane Doe").await.unwrap();
+
+    let ids: Vec<_> = tickets.iter().map(|t| t.id).collect();
+    assert_eq!(ids, [TicketID(101), TicketID(102), TicketID(103)]);
+    let requests = server.received_requests().await.unwrap();
+    let first = &input_data(&requests[0])["list_info"];
+    assert_eq!(first["start_index"], 1);
+    assert_eq!(
+        first["search_criteria"]["children"][0]["field"],
+        "requester.name"
+    );
+    assert_eq!(first["search_criteria"]["children"][0]["value"], "Jane Doe");
+    assert_eq!(input_data(&requests[1])["list_info"]["start_index"], 3);
+}
+
+#[tokio::test]
+async fn bulk_assign_assigns_every_ticket() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("PUT"))
+        .and(path_regex(r"^/api/v3/requests/\d+/assign$"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "response_status": success_status() })),
+        )
+        .expect(4)
+        .mount(&server)
+        .await;
+
+    let ids = [TicketID(1), TicketID(2), TicketID(3), TicketID(4)];
+    let results = sdp.bulk_assign(&ids, "On Call").await;
+
+    assert_eq!(results.len(), 4);
+    assert!(results.iter().all(Result::is_ok));
+    let requests = server.received_requests().await.unwrap();
+    let mut assigned: Vec<_> = requests.iter().map(|r| r.url.path().to_string()).collect();
+    assigned.sort();
+    assert_eq!(
+        assigned,
+        (1..=4)
+            .map(|id| format!("/api/v3/requests/{id}/assign"))
+            .collect::<Vec<_>>()
+    );
+    assert!(
+        requests
+            .iter()
+            .all(|r| input_data(r)["request"]["technician"]["name"] == "On Call")
+    );
+}
+
+#[tokio::test]
+async fn mark_first_response_sends_flag() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v3/requests/100/notes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "note": note_json(7, "On it"),
+            "response_status": success_status(),
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let note = sdp.ticket(100).mark_first_response("On it").await.unwrap();
+
+    assert_eq!(note.description, "On it");
+    let requests = server.received_requests().await.unwrap();
+    let sent = &input_data(&requests[0])["note"];
+    assert_eq!(sent["description"], "On it");
+    assert_eq!(sent["mark_first_response"], true);
+}
+
+#[tokio::test]
+async fn include_trashed_is_sent_in_list_info() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/requests"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "requests": [],
+            "response_status": [success_status()],
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    sdp.tickets()
+        .search()
+        .include_trashed()
+        .fetch()
+        .await
+        .unwrap();
+    sdp.tickets().search().fetch().await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let with = &input_data(&requests[0])["list_info"];
+    assert_eq!(with["include_trashed"], true);
+    assert_eq!(with["row_count"], 100);
+    assert!(
+        input_data(&requests[1])["list_info"]
+            .get("include_trashed")
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn close_returns_operation_result() {
+    let (server, sdp) = setup().await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100/close"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "request": {
+                "id": "100",
+                "last_updated_time": { "display_value": "", "value": "1700000000000" },
+            },
+            "response_status": {
+                "status_code": 2000,
+                "status": "success",
+                "messages": [{
+                    "status_code": 2000,
+                    "type": "success",
+                    "message": "Request closed successfully.",
+                }],
+            },
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/requests/100/assign"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "response_status": success_status() })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let closed = sdp.ticket(100).close("done").await.unwrap();
+    let assigned = sdp.ticket(100).assign("Tech One").await.unwrap();
+
+    assert_eq!(closed.status_code, 2000);
+    assert_eq!(
+        closed.message.as_deref(),
+        Some("Request closed successfully.")
+    );
+    assert_eq!(closed.updated_time.unwrap().value, "1700000000000");
+    assert_eq!(assigned.status_code, 2000);
+    assert!(assigned.updated_time.is_none());
+}